@@ -0,0 +1,93 @@
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+
+use bitmagic::sort::{dense_rank_u32, sort_unique_u32};
+use bitmagic::SparseVector;
+
+/// Deterministic pseudo-random ids below `max`.
+fn ids(n: usize, max: u32) -> Vec<u32> {
+    let mut state = 0x2545_f491u32;
+    (0..n)
+        .map(|_| {
+            state ^= state << 13;
+            state ^= state >> 17;
+            state ^= state << 5;
+            state % max
+        })
+        .collect()
+}
+
+fn sort_unique(c: &mut Criterion) {
+    let mut group = c.benchmark_group("sort_unique");
+
+    for &(n, max) in &[
+        (100_000, 1_000_000),
+        (1_000_000, 1_000_000),
+        (1_000_000, 1 << 30),
+    ] {
+        let values = ids(n, max);
+        let label = format!("{}/{}", n, max);
+
+        group.bench_with_input(BenchmarkId::new("bvector", &label), &values, |b, values| {
+            b.iter(|| {
+                let mut values = values.clone();
+                sort_unique_u32(&mut values);
+                values
+            })
+        });
+        group.bench_with_input(
+            BenchmarkId::new("sort_unstable+dedup", &label),
+            &values,
+            |b, values| {
+                b.iter(|| {
+                    let mut values = values.clone();
+                    values.sort_unstable();
+                    values.dedup();
+                    values
+                })
+            },
+        );
+    }
+
+    group.finish();
+}
+
+fn dense_rank(c: &mut Criterion) {
+    let values = ids(1_000_000, 1_000_000);
+
+    let mut group = c.benchmark_group("dense_rank");
+    group.bench_function("rank_select", |b| b.iter(|| dense_rank_u32(&values)));
+    group.bench_function("sort_unstable+binary_search", |b| {
+        b.iter(|| {
+            let mut distinct = values.clone();
+            distinct.sort_unstable();
+            distinct.dedup();
+            values
+                .iter()
+                .map(|value| distinct.binary_search(value).unwrap() as u32)
+                .collect::<Vec<_>>()
+        })
+    });
+    group.finish();
+}
+
+fn argsort(c: &mut Criterion) {
+    let values = ids(1_000_000, 1000);
+
+    let mut sv = SparseVector::new();
+    sv.import_from_slice(0, &values);
+    sv.optimize();
+
+    let mut group = c.benchmark_group("argsort");
+    group.bench_function("sparse_vector", |b| b.iter(|| sv.argsort()));
+    group.bench_function("sort_by_key", |b| {
+        b.iter(|| {
+            let mut order: Vec<usize> = (0..values.len()).collect();
+            order.sort_by_key(|&row| values[row]);
+            order
+        })
+    });
+    group.finish();
+}
+
+criterion_group!(benches, sort_unique, dense_rank, argsort);
+criterion_main!(benches);
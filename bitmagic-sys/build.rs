@@ -15,8 +15,10 @@ fn main() {
         .include("BitMagic/lang-maps/libbm/include")
         .include("BitMagic/lang-maps/libbm/src")
         .include("BitMagic/src")
+        .include("ext")
         .flag_if_supported("-std=c++17")
         .file("BitMagic/lang-maps/libbm/src/libbm.cpp")
+        .file("ext/libbm_ext.cpp")
         //.define("BM64ADDR", "1")
         .define("BM_SIMD_NO", "1")
         .define("BM_NO_STL", "1");
@@ -32,7 +34,8 @@ fn main() {
 fn generate_bindings() {
     let bindings = bindgen::Builder::default()
         .clang_arg("-I./BitMagic/lang-maps/libbm")
-        .header("BitMagic/lang-maps/libbm/include/libbm.h")
+        .clang_arg("-I./BitMagic/lang-maps/libbm/include")
+        .header("ext/libbm_ext.h")
         .size_t_is_usize(true)
         .use_core()
         .generate()
@@ -0,0 +1,1577 @@
+/*
+ Extensions to the libbm C API used by the Rust bindings.
+
+ Handles are compatible with the ones created by libbm: a bvector handle
+ is a pointer to a bm::bvector<> allocated with malloc + placement new,
+ so objects created here can be released with BM_bvector_free().
+*/
+
+#include <stdlib.h>
+#include <string.h>
+#include <new>
+#include <utility>
+
+#include "bm.h"
+#include "bmalgo.h"
+#include "bmintervals.h"
+#include "bmsparsevec.h"
+#include "bmsparsevec_algo.h"
+#include "bmsparsevec_compr.h"
+#include "bmsparsevec_serial.h"
+#include "bmstrsparsevec.h"
+
+#include "libbm_ext.h"
+
+typedef bm::bvector<> TBM_bvector;
+typedef TBM_bvector::rs_index_type TBM_rs_index;
+typedef bm::sparse_vector<unsigned int, TBM_bvector> TBM_sparse_vector_u32;
+typedef bm::sparse_vector<unsigned long long, TBM_bvector> TBM_sparse_vector_u64;
+typedef bm::rsc_sparse_vector<unsigned int, TBM_sparse_vector_u32> TBM_rsc_sparse_vector_u32;
+typedef bm::rsc_sparse_vector<unsigned long long, TBM_sparse_vector_u64>
+    TBM_rsc_sparse_vector_u64;
+typedef bm::str_sparse_vector<char, TBM_bvector, BM_STR_SPARSE_VECTOR_MAX_STR>
+    TBM_str_sparse_vector;
+
+// str_sparse_vector scanner, with the vector it searches
+struct TBM_str_sv_scanner
+{
+    const TBM_str_sparse_vector* sv;
+    bm::sparse_vector_scanner<TBM_str_sparse_vector> scanner;
+
+    TBM_str_sv_scanner() : sv(0) {}
+};
+
+// str_sparse_vector iterator, with the vector needed to look up NULLs
+struct TBM_str_sv_iterator
+{
+    const TBM_str_sparse_vector* sv;
+    TBM_str_sparse_vector::const_iterator it;
+
+    TBM_str_sv_iterator(const TBM_str_sparse_vector* v, unsigned int from)
+        : sv(v), it(v)
+    {
+        it.go_to(from);
+    }
+};
+
+#define BM_EXT_TRY try
+#define BM_EXT_CATCH_ALL                 \
+    catch (std::bad_alloc&)              \
+    {                                    \
+        return BM_ERR_BADALLOC;          \
+    }                                    \
+    catch (...)                          \
+    {                                    \
+        return BM_ERR_BADARG;            \
+    }
+
+// construct a T with malloc + placement new, as libbm does for bvectors
+template <class T, class... Args>
+int ext_construct(void** h, Args&&... args)
+{
+    if (!h)
+        return BM_ERR_BADARG;
+    *h = 0;
+
+    void* mem = ::malloc(sizeof(T));
+    if (!mem)
+        return BM_ERR_BADALLOC;
+
+    BM_EXT_TRY
+    {
+        *h = new (mem) T(std::forward<Args>(args)...);
+    }
+    catch (std::bad_alloc&)
+    {
+        ::free(mem);
+        return BM_ERR_BADALLOC;
+    }
+    catch (...)
+    {
+        ::free(mem);
+        return BM_ERR_BADARG;
+    }
+
+    return BM_OK;
+}
+
+// destroy and release an object created with ext_construct
+template <class T>
+int ext_free(void* h)
+{
+    if (h)
+    {
+        ((T*)h)->~T();
+        ::free(h);
+    }
+    return BM_OK;
+}
+
+// ------------------------------------------------------------------
+// bvector word import/export
+// ------------------------------------------------------------------
+
+int BM_bvector_import_u32(void* h,
+                          const unsigned int* words,
+                          size_t word_count)
+{
+    if (!h || (!words && word_count))
+        return BM_ERR_BADARG;
+    // the last word may only use the addressable bits
+    size_t max_words = (size_t(bm::id_max) + 31) / 32;
+    if (word_count > max_words ||
+        (word_count == max_words && (words[word_count - 1] >> (bm::id_max % 32))))
+        return BM_ERR_RANGE;
+
+    BM_EXT_TRY
+    {
+        TBM_bvector* bv = (TBM_bvector*)h;
+        bv->clear(true);
+        if (word_count)
+            bm::export_array(*bv, words, words + word_count);
+
+        size_t bits = word_count * 32;
+        bv->resize(TBM_bvector::size_type(bits < bm::id_max ? bits : bm::id_max));
+    }
+    BM_EXT_CATCH_ALL
+
+    return BM_OK;
+}
+
+int BM_bvector_export_u32(void* h,
+                          unsigned int* words,
+                          size_t word_count)
+{
+    if (!h || (!words && word_count))
+        return BM_ERR_BADARG;
+
+    BM_EXT_TRY
+    {
+        const TBM_bvector* bv = (TBM_bvector*)h;
+        ::memset(words, 0, word_count * sizeof(unsigned int));
+
+        const TBM_bvector::blocks_manager_type& bman = bv->get_blocks_manager();
+
+        // copy block by block: bit blocks as they are, GAP blocks decoded,
+        // empty blocks left zeroed
+        const size_t block_words = bm::set_block_size;
+        for (size_t first = 0; first < word_count; first += block_words)
+        {
+            size_t nb = first / block_words;
+            const bm::word_t* block = bman.get_block_ptr(
+                unsigned(nb >> bm::set_array_shift), unsigned(nb & bm::set_array_mask));
+            if (!block)
+                continue;
+
+            size_t n = word_count - first;
+            if (n > block_words)
+                n = block_words;
+
+            if (IS_FULL_BLOCK(block))
+            {
+                ::memset(words + first, 0xFF, n * sizeof(unsigned int));
+            }
+            else if (BM_IS_GAP(block))
+            {
+                bm::word_t tmp[bm::set_block_size];
+                bm::gap_convert_to_bitset(tmp, BMGAP_PTR(block));
+                ::memcpy(words + first, tmp, n * sizeof(unsigned int));
+            }
+            else
+            {
+                ::memcpy(words + first, block, n * sizeof(unsigned int));
+            }
+        }
+    }
+    BM_EXT_CATCH_ALL
+
+    return BM_OK;
+}
+
+int BM_bvector_export_ids(void* h,
+                          unsigned int* ids,
+                          size_t ids_size,
+                          size_t* pcount)
+{
+    if (!h || !pcount || (!ids && ids_size))
+        return BM_ERR_BADARG;
+
+    BM_EXT_TRY
+    {
+        const TBM_bvector* bv = (TBM_bvector*)h;
+        size_t n = 0;
+
+        TBM_bvector::enumerator en = bv->first();
+        for (; en.valid() && n < ids_size; ++en)
+            ids[n++] = *en;
+        *pcount = n;
+    }
+    BM_EXT_CATCH_ALL
+
+    return BM_OK;
+}
+
+int BM_bvector_export_runs(void* h,
+                           unsigned int from,
+                           unsigned int* runs,
+                           size_t runs_size,
+                           size_t* pcount)
+{
+    if (!h || !pcount || (!runs && runs_size))
+        return BM_ERR_BADARG;
+
+    BM_EXT_TRY
+    {
+        const TBM_bvector* bv = (TBM_bvector*)h;
+        size_t n = 0;
+
+        bm::interval_enumerator<TBM_bvector> ien(*bv, from, false);
+        for (; ien.valid() && n < runs_size; ien.advance())
+        {
+            runs[2 * n] = ien.start();
+            runs[2 * n + 1] = ien.end();
+            ++n;
+        }
+        *pcount = n;
+    }
+    BM_EXT_CATCH_ALL
+
+    return BM_OK;
+}
+
+int BM_bvector_find_zero(void* h,
+                         unsigned int from,
+                         unsigned int* ppos,
+                         int* pfound)
+{
+    if (!h || !ppos || !pfound)
+        return BM_ERR_BADARG;
+
+    BM_EXT_TRY
+    {
+        const TBM_bvector* bv = (TBM_bvector*)h;
+        *pfound = 0;
+
+        if (from >= bm::id_max)
+            return BM_OK;
+        if (!bv->test(from))
+        {
+            *ppos = from;
+            *pfound = 1;
+            return BM_OK;
+        }
+
+        // from is in a run of enabled bits: the zero is right after it
+        bm::interval_enumerator<TBM_bvector> ien(*bv, from, false);
+        if (ien.valid() && ien.end() < bm::id_max - 1)
+        {
+            *ppos = ien.end() + 1;
+            *pfound = 1;
+        }
+    }
+    BM_EXT_CATCH_ALL
+
+    return BM_OK;
+}
+
+// shifts of up to this many bits are done one bit at a time, larger ones
+// rebuild the vector out of its runs
+static const unsigned int ext_shift_loop_max = 16;
+
+// move every bit n positions towards the end or the start of bv, dropping
+// the bits moved out of [0, size)
+static void ext_shift(TBM_bvector& bv, unsigned int n, bool right)
+{
+    const TBM_bvector::size_type size = bv.size();
+    if (!n)
+        return;
+
+    if (n <= ext_shift_loop_max)
+    {
+        for (unsigned int i = 0; i < n; ++i)
+        {
+            if (right)
+                bv.shift_right();
+            else
+                bv.shift_left();
+        }
+    }
+    else
+    {
+        typedef unsigned long long pos_type;
+        const pos_type last = pos_type(size) - 1;
+
+        TBM_bvector shifted;
+        bm::interval_enumerator<TBM_bvector> ien(bv, right ? 0 : n, false);
+        for (; ien.valid(); ien.advance())
+        {
+            pos_type start = ien.start();
+            pos_type end = ien.end();
+            if (right)
+            {
+                if (start + n > last)
+                    break;
+                end = end + n > last ? last : end + n;
+                shifted.set_range(TBM_bvector::size_type(start + n),
+                                  TBM_bvector::size_type(end));
+            }
+            else
+            {
+                if (end < n)
+                    continue;
+                if (start < n)
+                    start = n;
+                shifted.set_range(TBM_bvector::size_type(start - n),
+                                  TBM_bvector::size_type(end - n));
+            }
+        }
+        bv.swap(shifted);
+    }
+
+    if (bv.size() != size)
+        bv.resize(size);
+}
+
+int BM_bvector_shift_right(void* h, unsigned int n)
+{
+    if (!h)
+        return BM_ERR_BADARG;
+
+    BM_EXT_TRY
+    {
+        ext_shift(*(TBM_bvector*)h, n, true);
+    }
+    BM_EXT_CATCH_ALL
+
+    return BM_OK;
+}
+
+int BM_bvector_shift_left(void* h, unsigned int n)
+{
+    if (!h)
+        return BM_ERR_BADARG;
+
+    BM_EXT_TRY
+    {
+        ext_shift(*(TBM_bvector*)h, n, false);
+    }
+    BM_EXT_CATCH_ALL
+
+    return BM_OK;
+}
+
+int BM_bvector_insert_bit(void* h, unsigned int pos, int value)
+{
+    if (!h)
+        return BM_ERR_BADARG;
+
+    BM_EXT_TRY
+    {
+        TBM_bvector* bv = (TBM_bvector*)h;
+        const TBM_bvector::size_type size = bv->size();
+        if (pos >= size)
+            return BM_ERR_RANGE;
+
+        bv->insert(pos, value != 0);
+        if (bv->size() != size)
+            bv->resize(size);
+    }
+    BM_EXT_CATCH_ALL
+
+    return BM_OK;
+}
+
+int BM_bvector_erase_bit(void* h, unsigned int pos)
+{
+    if (!h)
+        return BM_ERR_BADARG;
+
+    BM_EXT_TRY
+    {
+        TBM_bvector* bv = (TBM_bvector*)h;
+        const TBM_bvector::size_type size = bv->size();
+        if (pos >= size)
+            return BM_ERR_RANGE;
+
+        bv->erase(pos);
+        if (bv->size() != size)
+            bv->resize(size);
+    }
+    BM_EXT_CATCH_ALL
+
+    return BM_OK;
+}
+
+int BM_bvector_copy_range(void* hdst,
+                          void* hsrc,
+                          unsigned int left,
+                          unsigned int right)
+{
+    if (!hdst || !hsrc)
+        return BM_ERR_BADARG;
+    if (left > right)
+        return BM_ERR_RANGE;
+
+    BM_EXT_TRY
+    {
+        TBM_bvector* dst = (TBM_bvector*)hdst;
+        const TBM_bvector* src = (TBM_bvector*)hsrc;
+        dst->copy_range(*src, left, right);
+        dst->resize(src->size());
+    }
+    BM_EXT_CATCH_ALL
+
+    return BM_OK;
+}
+
+int BM_bvector_keep_range(void* h, unsigned int left, unsigned int right)
+{
+    if (!h)
+        return BM_ERR_BADARG;
+    if (left > right)
+        return BM_ERR_RANGE;
+
+    BM_EXT_TRY
+    {
+        ((TBM_bvector*)h)->keep_range(left, right);
+    }
+    BM_EXT_CATCH_ALL
+
+    return BM_OK;
+}
+
+// ------------------------------------------------------------------
+// bvector rank-select index
+// ------------------------------------------------------------------
+
+int BM_rs_index_construct(void** h)
+{
+    return ext_construct<TBM_rs_index>(h);
+}
+
+int BM_rs_index_free(void* h)
+{
+    return ext_free<TBM_rs_index>(h);
+}
+
+int BM_bvector_build_rs_index(void* h, void* hrs)
+{
+    if (!h || !hrs)
+        return BM_ERR_BADARG;
+
+    BM_EXT_TRY
+    {
+        const TBM_bvector* bv = (TBM_bvector*)h;
+        bv->build_rs_index((TBM_rs_index*)hrs);
+    }
+    BM_EXT_CATCH_ALL
+
+    return BM_OK;
+}
+
+int BM_bvector_count_to_rs(void* h,
+                           void* hrs,
+                           unsigned int right,
+                           unsigned int* pcount)
+{
+    if (!h || !hrs || !pcount)
+        return BM_ERR_BADARG;
+
+    const TBM_bvector* bv = (TBM_bvector*)h;
+    *pcount = bv->count_to(right, *(const TBM_rs_index*)hrs);
+
+    return BM_OK;
+}
+
+int BM_bvector_count_range_rs(void* h,
+                              void* hrs,
+                              unsigned int left,
+                              unsigned int right,
+                              unsigned int* pcount)
+{
+    if (!h || !hrs || !pcount)
+        return BM_ERR_BADARG;
+    if (left > right)
+        return BM_ERR_RANGE;
+
+    const TBM_bvector* bv = (TBM_bvector*)h;
+    *pcount = bv->count_range(left, right, *(const TBM_rs_index*)hrs);
+
+    return BM_OK;
+}
+
+int BM_bvector_select_rs(void* h,
+                         void* hrs,
+                         unsigned int rank,
+                         unsigned int* ppos,
+                         int* pfound)
+{
+    if (!h || !hrs || !ppos || !pfound)
+        return BM_ERR_BADARG;
+
+    const TBM_bvector* bv = (TBM_bvector*)h;
+    TBM_bvector::size_type pos = 0;
+    *pfound = bv->select(rank, pos, *(const TBM_rs_index*)hrs);
+    *ppos = pos;
+
+    return BM_OK;
+}
+
+// ------------------------------------------------------------------
+// sparse_vector
+// ------------------------------------------------------------------
+
+// rsc_sparse_vector needs its rank-select index rebuilt after changes
+template<class SV>
+static void sv_sync(SV&)
+{
+}
+
+template<class Val, class SV>
+static void sv_sync(bm::rsc_sparse_vector<Val, SV>& rsc)
+{
+    rsc.sync();
+}
+
+template<class SV>
+static int sv_size(void* h, unsigned int* psize)
+{
+    if (!h || !psize)
+        return BM_ERR_BADARG;
+
+    *psize = ((const SV*)h)->size();
+
+    return BM_OK;
+}
+
+template<class SV>
+static int sv_push_back(void* h, typename SV::value_type value)
+{
+    if (!h)
+        return BM_ERR_BADARG;
+
+    BM_EXT_TRY
+    {
+        SV* sv = (SV*)h;
+        if (sv->size() >= bm::id_max - 1)
+            return BM_ERR_RANGE;
+        sv->push_back(value);
+    }
+    BM_EXT_CATCH_ALL
+
+    return BM_OK;
+}
+
+template<class SV>
+static int sv_set(void* h, unsigned int idx, typename SV::value_type value)
+{
+    if (!h)
+        return BM_ERR_BADARG;
+    if (idx >= bm::id_max - 1)
+        return BM_ERR_RANGE;
+
+    BM_EXT_TRY
+    {
+        ((SV*)h)->set(idx, value);
+    }
+    BM_EXT_CATCH_ALL
+
+    return BM_OK;
+}
+
+template<class SV>
+static int sv_set_null(void* h, unsigned int idx)
+{
+    if (!h)
+        return BM_ERR_BADARG;
+    if (idx >= bm::id_max - 1)
+        return BM_ERR_RANGE;
+
+    BM_EXT_TRY
+    {
+        SV* sv = (SV*)h;
+        if (idx >= sv->size())
+            sv->resize(idx + 1);
+        else
+            sv->set_null(idx);
+    }
+    BM_EXT_CATCH_ALL
+
+    return BM_OK;
+}
+
+template<class SV>
+static int sv_get(void* h,
+                  unsigned int idx,
+                  typename SV::value_type* pvalue,
+                  int* pnull)
+{
+    if (!h || !pvalue || !pnull)
+        return BM_ERR_BADARG;
+
+    const SV* sv = (const SV*)h;
+    if (idx >= sv->size())
+        return BM_ERR_RANGE;
+
+    *pnull = sv->is_null(idx);
+    *pvalue = *pnull ? 0 : sv->get(idx);
+
+    return BM_OK;
+}
+
+template<class SV>
+static int sv_import(void* h,
+                     const typename SV::value_type* arr,
+                     size_t arr_size,
+                     unsigned int offset)
+{
+    if (!h || (!arr && arr_size))
+        return BM_ERR_BADARG;
+    if (arr_size >= size_t(bm::id_max - 1) - offset)
+        return BM_ERR_RANGE;
+
+    BM_EXT_TRY
+    {
+        if (arr_size)
+            ((SV*)h)->import(arr, typename SV::size_type(arr_size), offset);
+    }
+    BM_EXT_CATCH_ALL
+
+    return BM_OK;
+}
+
+template<class SV>
+static int sv_decode(void* h,
+                     typename SV::value_type* arr,
+                     unsigned int from,
+                     size_t size,
+                     size_t* pdecoded)
+{
+    if (!h || !pdecoded || (!arr && size))
+        return BM_ERR_BADARG;
+
+    BM_EXT_TRY
+    {
+        const SV* sv = (const SV*)h;
+        *pdecoded = 0;
+        if (size && from < sv->size())
+            *pdecoded = sv->decode(arr, from, typename SV::size_type(size));
+    }
+    BM_EXT_CATCH_ALL
+
+    return BM_OK;
+}
+
+template<class SV>
+static int sv_optimize(void* h)
+{
+    if (!h)
+        return BM_ERR_BADARG;
+
+    BM_EXT_TRY
+    {
+        SV* sv = (SV*)h;
+        sv->optimize();
+        sv_sync(*sv);
+    }
+    BM_EXT_CATCH_ALL
+
+    return BM_OK;
+}
+
+template<class SV>
+static int sv_serialize(void* h, unsigned char** pbuf, size_t* psize)
+{
+    if (!h || !pbuf || !psize)
+        return BM_ERR_BADARG;
+    *pbuf = 0;
+    *psize = 0;
+
+    BM_EXT_TRY
+    {
+        bm::sparse_vector_serial_layout<SV> sv_lay;
+        bm::sparse_vector_serializer<SV> sv_serializer;
+        sv_serializer.serialize(*(const SV*)h, sv_lay);
+
+        size_t size = sv_lay.size();
+        unsigned char* buf = (unsigned char*)::malloc(size ? size : 1);
+        if (!buf)
+            return BM_ERR_BADALLOC;
+        ::memcpy(buf, sv_lay.buf(), size);
+
+        *pbuf = buf;
+        *psize = size;
+    }
+    BM_EXT_CATCH_ALL
+
+    return BM_OK;
+}
+
+template<class SV>
+static int sv_deserialize(void* h, const unsigned char* buf, size_t buf_size)
+{
+    if (!h || !buf || !buf_size)
+        return BM_ERR_BADARG;
+
+    BM_EXT_TRY
+    {
+        SV* sv = (SV*)h;
+        bm::sparse_vector_deserializer<SV> sv_deserializer;
+        sv_deserializer.deserialize(*sv, buf);
+        // rank lookups on a deserialized rsc_sparse_vector need its index
+        sv_sync(*sv);
+        // only null-able vectors are created by the bindings
+        if (!sv->get_null_bvector())
+            return BM_ERR_BADARG;
+    }
+    BM_EXT_CATCH_ALL
+
+    return BM_OK;
+}
+
+template<class SV>
+static int sv_get_null_bvector(void* h, void** pbv)
+{
+    if (!h || !pbv)
+        return BM_ERR_BADARG;
+
+    *pbv = (void*)((const SV*)h)->get_null_bvector();
+
+    return *pbv ? BM_OK : BM_ERR_BADARG;
+}
+
+template<class SV>
+static int sv_get_plane(void* h, unsigned int i, void** pbv)
+{
+    if (!h || !pbv)
+        return BM_ERR_BADARG;
+    if (i >= sizeof(typename SV::value_type) * 8)
+        return BM_ERR_RANGE;
+
+    // planes are allocated on demand: 0 means all the values have bit i unset
+    *pbv = (void*)((const SV*)h)->get_plane(i);
+
+    return BM_OK;
+}
+
+int BM_ext_buffer_free(void* buf)
+{
+    ::free(buf);
+    return BM_OK;
+}
+
+#define BM_EXT_SPARSE_VECTOR_API(SUFFIX, SV)                                  \
+    int BM_sparse_vector_##SUFFIX##_construct(void** h)                       \
+    {                                                                         \
+        return ext_construct<SV>(h, bm::use_null);                            \
+    }                                                                         \
+    int BM_sparse_vector_##SUFFIX##_construct_copy(void** h, void* hfrom)     \
+    {                                                                         \
+        if (!hfrom)                                                           \
+            return BM_ERR_BADARG;                                             \
+        return ext_construct<SV>(h, *(const SV*)hfrom);                       \
+    }                                                                         \
+    int BM_sparse_vector_##SUFFIX##_free(void* h)                             \
+    {                                                                         \
+        return ext_free<SV>(h);                                               \
+    }                                                                         \
+    int BM_sparse_vector_##SUFFIX##_size(void* h, unsigned int* psize)        \
+    {                                                                         \
+        return sv_size<SV>(h, psize);                                         \
+    }                                                                         \
+    int BM_sparse_vector_##SUFFIX##_push_back(void* h, SV::value_type value)  \
+    {                                                                         \
+        return sv_push_back<SV>(h, value);                                    \
+    }                                                                         \
+    int BM_sparse_vector_##SUFFIX##_set(void* h,                              \
+                                        unsigned int idx,                     \
+                                        SV::value_type value)                 \
+    {                                                                         \
+        return sv_set<SV>(h, idx, value);                                     \
+    }                                                                         \
+    int BM_sparse_vector_##SUFFIX##_set_null(void* h, unsigned int idx)       \
+    {                                                                         \
+        return sv_set_null<SV>(h, idx);                                       \
+    }                                                                         \
+    int BM_sparse_vector_##SUFFIX##_get(void* h,                              \
+                                        unsigned int idx,                     \
+                                        SV::value_type* pvalue,               \
+                                        int* pnull)                           \
+    {                                                                         \
+        return sv_get<SV>(h, idx, pvalue, pnull);                             \
+    }                                                                         \
+    int BM_sparse_vector_##SUFFIX##_import(void* h,                           \
+                                           const SV::value_type* arr,         \
+                                           size_t arr_size,                   \
+                                           unsigned int offset)               \
+    {                                                                         \
+        return sv_import<SV>(h, arr, arr_size, offset);                       \
+    }                                                                         \
+    int BM_sparse_vector_##SUFFIX##_decode(void* h,                           \
+                                           SV::value_type* arr,               \
+                                           unsigned int from,                 \
+                                           size_t size,                       \
+                                           size_t* pdecoded)                  \
+    {                                                                         \
+        return sv_decode<SV>(h, arr, from, size, pdecoded);                   \
+    }                                                                         \
+    int BM_sparse_vector_##SUFFIX##_optimize(void* h)                         \
+    {                                                                         \
+        return sv_optimize<SV>(h);                                            \
+    }                                                                         \
+    int BM_sparse_vector_##SUFFIX##_serialize(void* h,                        \
+                                              unsigned char** pbuf,           \
+                                              size_t* psize)                  \
+    {                                                                         \
+        return sv_serialize<SV>(h, pbuf, psize);                              \
+    }                                                                         \
+    int BM_sparse_vector_##SUFFIX##_deserialize(void* h,                      \
+                                                const unsigned char* buf,     \
+                                                size_t buf_size)              \
+    {                                                                         \
+        return sv_deserialize<SV>(h, buf, buf_size);                          \
+    }                                                                         \
+    int BM_sparse_vector_##SUFFIX##_get_null_bvector(void* h, void** pbv)     \
+    {                                                                         \
+        return sv_get_null_bvector<SV>(h, pbv);                               \
+    }                                                                         \
+    int BM_sparse_vector_##SUFFIX##_get_plane(void* h,                        \
+                                              unsigned int i,                 \
+                                              void** pbv)                     \
+    {                                                                         \
+        return sv_get_plane<SV>(h, i, pbv);                                   \
+    }
+
+BM_EXT_SPARSE_VECTOR_API(u32, TBM_sparse_vector_u32)
+BM_EXT_SPARSE_VECTOR_API(u64, TBM_sparse_vector_u64)
+
+// ------------------------------------------------------------------
+// rsc_sparse_vector
+// ------------------------------------------------------------------
+
+template<class RSC>
+static int rsc_push_back(void* h, unsigned int idx, typename RSC::value_type value)
+{
+    if (!h)
+        return BM_ERR_BADARG;
+    if (idx >= bm::id_max - 1)
+        return BM_ERR_RANGE;
+
+    BM_EXT_TRY
+    {
+        RSC* rsc = (RSC*)h;
+        if (idx < rsc->size())
+            return BM_ERR_RANGE;
+        rsc->push_back(idx, value);
+    }
+    BM_EXT_CATCH_ALL
+
+    return BM_OK;
+}
+
+template<class RSC>
+static int rsc_sync(void* h)
+{
+    if (!h)
+        return BM_ERR_BADARG;
+
+    BM_EXT_TRY
+    {
+        ((RSC*)h)->sync();
+    }
+    BM_EXT_CATCH_ALL
+
+    return BM_OK;
+}
+
+template<class RSC>
+static int rsc_load_from(void* h, void* hsv)
+{
+    if (!h || !hsv)
+        return BM_ERR_BADARG;
+
+    BM_EXT_TRY
+    {
+        RSC* rsc = (RSC*)h;
+        rsc->load_from(*(const typename RSC::sparse_vector_type*)hsv);
+        rsc->sync();
+    }
+    BM_EXT_CATCH_ALL
+
+    return BM_OK;
+}
+
+template<class RSC>
+static int rsc_load_to(void* h, void* hsv)
+{
+    if (!h || !hsv)
+        return BM_ERR_BADARG;
+
+    BM_EXT_TRY
+    {
+        ((const RSC*)h)->load_to(*(typename RSC::sparse_vector_type*)hsv);
+    }
+    BM_EXT_CATCH_ALL
+
+    return BM_OK;
+}
+
+#define BM_EXT_RSC_SPARSE_VECTOR_API(SUFFIX, RSC)                             \
+    int BM_rsc_sparse_vector_##SUFFIX##_construct(void** h)                   \
+    {                                                                         \
+        return ext_construct<RSC>(h);                                         \
+    }                                                                         \
+    int BM_rsc_sparse_vector_##SUFFIX##_construct_copy(void** h, void* hfrom) \
+    {                                                                         \
+        if (!hfrom)                                                           \
+            return BM_ERR_BADARG;                                             \
+        return ext_construct<RSC>(h, *(const RSC*)hfrom);                     \
+    }                                                                         \
+    int BM_rsc_sparse_vector_##SUFFIX##_free(void* h)                         \
+    {                                                                         \
+        return ext_free<RSC>(h);                                              \
+    }                                                                         \
+    int BM_rsc_sparse_vector_##SUFFIX##_size(void* h, unsigned int* psize)    \
+    {                                                                         \
+        return sv_size<RSC>(h, psize);                                        \
+    }                                                                         \
+    int BM_rsc_sparse_vector_##SUFFIX##_push_back(void* h,                    \
+                                                  unsigned int idx,           \
+                                                  RSC::value_type value)      \
+    {                                                                         \
+        return rsc_push_back<RSC>(h, idx, value);                             \
+    }                                                                         \
+    int BM_rsc_sparse_vector_##SUFFIX##_sync(void* h)                         \
+    {                                                                         \
+        return rsc_sync<RSC>(h);                                              \
+    }                                                                         \
+    int BM_rsc_sparse_vector_##SUFFIX##_load_from(void* h, void* hsv)         \
+    {                                                                         \
+        return rsc_load_from<RSC>(h, hsv);                                    \
+    }                                                                         \
+    int BM_rsc_sparse_vector_##SUFFIX##_load_to(void* h, void* hsv)           \
+    {                                                                         \
+        return rsc_load_to<RSC>(h, hsv);                                      \
+    }                                                                         \
+    int BM_rsc_sparse_vector_##SUFFIX##_get(void* h,                          \
+                                            unsigned int idx,                 \
+                                            RSC::value_type* pvalue,          \
+                                            int* pnull)                       \
+    {                                                                         \
+        return sv_get<RSC>(h, idx, pvalue, pnull);                            \
+    }                                                                         \
+    int BM_rsc_sparse_vector_##SUFFIX##_decode(void* h,                       \
+                                               RSC::value_type* arr,          \
+                                               unsigned int from,             \
+                                               size_t size,                   \
+                                               size_t* pdecoded)              \
+    {                                                                         \
+        return sv_decode<RSC>(h, arr, from, size, pdecoded);                  \
+    }                                                                         \
+    int BM_rsc_sparse_vector_##SUFFIX##_optimize(void* h)                     \
+    {                                                                         \
+        return sv_optimize<RSC>(h);                                           \
+    }                                                                         \
+    int BM_rsc_sparse_vector_##SUFFIX##_serialize(void* h,                    \
+                                                  unsigned char** pbuf,       \
+                                                  size_t* psize)              \
+    {                                                                         \
+        return sv_serialize<RSC>(h, pbuf, psize);                             \
+    }                                                                         \
+    int BM_rsc_sparse_vector_##SUFFIX##_deserialize(void* h,                  \
+                                                    const unsigned char* buf, \
+                                                    size_t buf_size)          \
+    {                                                                         \
+        return sv_deserialize<RSC>(h, buf, buf_size);                         \
+    }                                                                         \
+    int BM_rsc_sparse_vector_##SUFFIX##_get_null_bvector(void* h, void** pbv) \
+    {                                                                         \
+        return sv_get_null_bvector<RSC>(h, pbv);                              \
+    }
+
+BM_EXT_RSC_SPARSE_VECTOR_API(u32, TBM_rsc_sparse_vector_u32)
+BM_EXT_RSC_SPARSE_VECTOR_API(u64, TBM_rsc_sparse_vector_u64)
+
+// ------------------------------------------------------------------
+// str_sparse_vector
+// ------------------------------------------------------------------
+
+// copy a NUL-terminated string into buf, truncating it to buf_size - 1
+static void ext_copy_str(const char* str, char* buf, size_t buf_size)
+{
+    if (!buf_size)
+        return;
+    size_t len = str ? ::strlen(str) : 0;
+    if (len >= buf_size)
+        len = buf_size - 1;
+    ::memcpy(buf, str, len);
+    buf[len] = 0;
+}
+
+// remapped vectors only accept the characters of their remapping: undo it
+// before storing str if it has other ones. The strings are moved to the
+// plain vector a block at a time, and each block is released from the
+// remapped vector once moved, so the vector is never held twice.
+static void str_sv_unmap(TBM_str_sparse_vector& sv, const char* str)
+{
+    if (!sv.is_remap())
+        return;
+
+    char remapped[BM_STR_SPARSE_VECTOR_MAX_STR];
+    if (sv.remap_tosv(remapped, BM_STR_SPARSE_VECTOR_MAX_STR, str))
+        return;
+
+    typedef TBM_str_sparse_vector::size_type size_type;
+    const size_type batch = 65536;
+    const size_type size = sv.size();
+
+    TBM_str_sparse_vector plain(bm::use_null);
+    char buf[BM_STR_SPARSE_VECTOR_MAX_STR];
+    for (size_type from = 0; from < size; from += batch)
+    {
+        size_type to = size - from > batch ? from + batch : size;
+        for (size_type idx = from; idx < to; ++idx)
+        {
+            if (sv.is_null(idx))
+                continue;
+            sv.get(idx, buf, BM_STR_SPARSE_VECTOR_MAX_STR);
+            plain.set(idx, buf);
+        }
+        sv.clear_range(from, to - 1, true);
+    }
+    plain.resize(size);
+    sv.swap(plain);
+}
+
+int BM_str_sparse_vector_construct(void** h)
+{
+    return ext_construct<TBM_str_sparse_vector>(h, bm::use_null);
+}
+
+int BM_str_sparse_vector_construct_copy(void** h, void* hfrom)
+{
+    if (!hfrom)
+        return BM_ERR_BADARG;
+    return ext_construct<TBM_str_sparse_vector>(h, *(const TBM_str_sparse_vector*)hfrom);
+}
+
+int BM_str_sparse_vector_free(void* h)
+{
+    return ext_free<TBM_str_sparse_vector>(h);
+}
+
+int BM_str_sparse_vector_size(void* h, unsigned int* psize)
+{
+    return sv_size<TBM_str_sparse_vector>(h, psize);
+}
+
+int BM_str_sparse_vector_max_str_len(void* h, size_t* plen)
+{
+    if (!h || !plen)
+        return BM_ERR_BADARG;
+
+    *plen = ((const TBM_str_sparse_vector*)h)->effective_max_str();
+
+    return BM_OK;
+}
+
+int BM_str_sparse_vector_push_back(void* h, const char* str)
+{
+    if (!h || !str)
+        return BM_ERR_BADARG;
+    if (::strlen(str) >= BM_STR_SPARSE_VECTOR_MAX_STR)
+        return BM_ERR_RANGE;
+
+    BM_EXT_TRY
+    {
+        TBM_str_sparse_vector* sv = (TBM_str_sparse_vector*)h;
+        if (sv->size() >= bm::id_max - 1)
+            return BM_ERR_RANGE;
+        str_sv_unmap(*sv, str);
+        sv->push_back(str);
+    }
+    BM_EXT_CATCH_ALL
+
+    return BM_OK;
+}
+
+int BM_str_sparse_vector_set(void* h, unsigned int idx, const char* str)
+{
+    if (!h || !str)
+        return BM_ERR_BADARG;
+    if (idx >= bm::id_max - 1 || ::strlen(str) >= BM_STR_SPARSE_VECTOR_MAX_STR)
+        return BM_ERR_RANGE;
+
+    BM_EXT_TRY
+    {
+        TBM_str_sparse_vector* sv = (TBM_str_sparse_vector*)h;
+        str_sv_unmap(*sv, str);
+        sv->set(idx, str);
+    }
+    BM_EXT_CATCH_ALL
+
+    return BM_OK;
+}
+
+int BM_str_sparse_vector_get(void* h,
+                             unsigned int idx,
+                             char* buf,
+                             size_t buf_size,
+                             int* pnull)
+{
+    if (!h || !buf || !buf_size || !pnull)
+        return BM_ERR_BADARG;
+
+    const TBM_str_sparse_vector* sv = (const TBM_str_sparse_vector*)h;
+    if (idx >= sv->size())
+        return BM_ERR_RANGE;
+
+    *pnull = sv->is_null(idx);
+    buf[0] = 0;
+    if (!*pnull)
+        sv->get(idx, buf, TBM_str_sparse_vector::size_type(buf_size));
+    buf[buf_size - 1] = 0;
+
+    return BM_OK;
+}
+
+int BM_str_sparse_vector_remap(void* h)
+{
+    if (!h)
+        return BM_ERR_BADARG;
+
+    BM_EXT_TRY
+    {
+        TBM_str_sparse_vector* sv = (TBM_str_sparse_vector*)h;
+        if (!sv->is_remap())
+            sv->remap();
+    }
+    BM_EXT_CATCH_ALL
+
+    return BM_OK;
+}
+
+int BM_str_sparse_vector_optimize(void* h)
+{
+    return sv_optimize<TBM_str_sparse_vector>(h);
+}
+
+int BM_str_sparse_vector_calc_stat(void* h, struct BM_bvector_statistics* pstat)
+{
+    if (!h || !pstat)
+        return BM_ERR_BADARG;
+
+    BM_EXT_TRY
+    {
+        TBM_str_sparse_vector::statistics st;
+        ((const TBM_str_sparse_vector*)h)->calc_stat(&st);
+
+        pstat->bit_blocks = st.bit_blocks;
+        pstat->gap_blocks = st.gap_blocks;
+        pstat->max_serialize_mem = st.max_serialize_mem;
+        pstat->memory_used = st.memory_used;
+    }
+    BM_EXT_CATCH_ALL
+
+    return BM_OK;
+}
+
+int BM_str_sparse_vector_serialize(void* h, unsigned char** pbuf, size_t* psize)
+{
+    return sv_serialize<TBM_str_sparse_vector>(h, pbuf, psize);
+}
+
+int BM_str_sparse_vector_deserialize(void* h, const unsigned char* buf, size_t buf_size)
+{
+    return sv_deserialize<TBM_str_sparse_vector>(h, buf, buf_size);
+}
+
+int BM_str_sparse_vector_get_null_bvector(void* h, void** pbv)
+{
+    return sv_get_null_bvector<TBM_str_sparse_vector>(h, pbv);
+}
+
+int BM_str_sparse_vector_iterator_construct(void** hit, void* h, unsigned int from)
+{
+    if (!h)
+        return BM_ERR_BADARG;
+    return ext_construct<TBM_str_sv_iterator>(hit, (const TBM_str_sparse_vector*)h, from);
+}
+
+int BM_str_sparse_vector_iterator_free(void* hit)
+{
+    return ext_free<TBM_str_sv_iterator>(hit);
+}
+
+int BM_str_sparse_vector_iterator_next(void* hit,
+                                       char* buf,
+                                       size_t buf_size,
+                                       int* pnull,
+                                       int* pvalid)
+{
+    if (!hit || !buf || !buf_size || !pnull || !pvalid)
+        return BM_ERR_BADARG;
+
+    BM_EXT_TRY
+    {
+        TBM_str_sv_iterator* sit = (TBM_str_sv_iterator*)hit;
+        *pvalid = sit->it.valid();
+        *pnull = 0;
+        buf[0] = 0;
+        if (!*pvalid)
+            return BM_OK;
+
+        *pnull = sit->sv->is_null(sit->it.pos());
+        if (!*pnull)
+            ext_copy_str(sit->it.value(), buf, buf_size);
+        ++sit->it;
+    }
+    BM_EXT_CATCH_ALL
+
+    return BM_OK;
+}
+
+// ------------------------------------------------------------------
+// sparse_vector search
+// ------------------------------------------------------------------
+
+// scanner results can include NULL elements, which are stored as 0; the
+// results are sized to the vector, so they can be combined with each other
+template<class SV>
+static void sv_finish_result(const SV& sv, TBM_bvector& bv)
+{
+    const TBM_bvector* bv_null = sv.get_null_bvector();
+    if (bv_null)
+        bv.bit_and(*bv_null);
+    bv.resize(sv.size());
+}
+
+template<class SV>
+static int sv_find_eq(void* h, typename SV::value_type value, void* hbv)
+{
+    if (!h || !hbv)
+        return BM_ERR_BADARG;
+
+    BM_EXT_TRY
+    {
+        const SV* sv = (const SV*)h;
+        TBM_bvector* bv = (TBM_bvector*)hbv;
+
+        bm::sparse_vector_scanner<SV> scanner;
+        scanner.find_eq(*sv, value, *bv);
+        sv_finish_result(*sv, *bv);
+    }
+    BM_EXT_CATCH_ALL
+
+    return BM_OK;
+}
+
+template<class SV>
+static int sv_find_range(void* h,
+                         typename SV::value_type from,
+                         typename SV::value_type to,
+                         void* hbv)
+{
+    if (!h || !hbv)
+        return BM_ERR_BADARG;
+    if (from > to)
+        return BM_ERR_RANGE;
+
+    BM_EXT_TRY
+    {
+        const SV* sv = (const SV*)h;
+        TBM_bvector* bv = (TBM_bvector*)hbv;
+
+        bm::sparse_vector_scanner<SV> scanner;
+        scanner.find_range(*sv, from, to, *bv);
+        sv_finish_result(*sv, *bv);
+    }
+    BM_EXT_CATCH_ALL
+
+    return BM_OK;
+}
+
+template<class SV>
+static int sv_find_any_of(void* h,
+                          const typename SV::value_type* values,
+                          size_t values_size,
+                          void* hbv)
+{
+    if (!h || !hbv || (!values && values_size))
+        return BM_ERR_BADARG;
+
+    BM_EXT_TRY
+    {
+        const SV* sv = (const SV*)h;
+        TBM_bvector* bv = (TBM_bvector*)hbv;
+        bv->clear();
+
+        bm::sparse_vector_scanner<SV> scanner;
+        TBM_bvector bv_found;
+        for (size_t i = 0; i < values_size; ++i)
+        {
+            scanner.find_eq(*sv, values[i], bv_found);
+            bv->bit_or(bv_found);
+        }
+        sv_finish_result(*sv, *bv);
+    }
+    BM_EXT_CATCH_ALL
+
+    return BM_OK;
+}
+
+template<class SV>
+static int sv_find_null(void* h, void* hbv)
+{
+    if (!h || !hbv)
+        return BM_ERR_BADARG;
+
+    BM_EXT_TRY
+    {
+        const SV* sv = (const SV*)h;
+        TBM_bvector* bv = (TBM_bvector*)hbv;
+        bv->clear();
+
+        if (sv->size())
+        {
+            bv->set_range(0, sv->size() - 1);
+            const TBM_bvector* bv_null = sv->get_null_bvector();
+            if (bv_null)
+                bv->bit_sub(*bv_null);
+        }
+        bv->resize(sv->size());
+    }
+    BM_EXT_CATCH_ALL
+
+    return BM_OK;
+}
+
+#define BM_EXT_SPARSE_VECTOR_SEARCH_API(SUFFIX, SV)                           \
+    int BM_sparse_vector_##SUFFIX##_find_eq(void* h,                          \
+                                            SV::value_type value,             \
+                                            void* hbv)                        \
+    {                                                                         \
+        return sv_find_eq<SV>(h, value, hbv);                                 \
+    }                                                                         \
+    int BM_sparse_vector_##SUFFIX##_find_range(void* h,                       \
+                                               SV::value_type from,           \
+                                               SV::value_type to,             \
+                                               void* hbv)                     \
+    {                                                                         \
+        return sv_find_range<SV>(h, from, to, hbv);                           \
+    }                                                                         \
+    int BM_sparse_vector_##SUFFIX##_find_any_of(void* h,                      \
+                                                const SV::value_type* values, \
+                                                size_t values_size,           \
+                                                void* hbv)                    \
+    {                                                                         \
+        return sv_find_any_of<SV>(h, values, values_size, hbv);               \
+    }                                                                         \
+    int BM_sparse_vector_##SUFFIX##_find_null(void* h, void* hbv)             \
+    {                                                                         \
+        return sv_find_null<SV>(h, hbv);                                      \
+    }
+
+BM_EXT_SPARSE_VECTOR_SEARCH_API(u32, TBM_sparse_vector_u32)
+BM_EXT_SPARSE_VECTOR_SEARCH_API(u64, TBM_sparse_vector_u64)
+
+// Largest not-NULL value of sv, found by narrowing the candidate elements
+// plane by plane from the highest bit; false if all the elements are NULL.
+static bool sv_u32_max(const TBM_sparse_vector_u32& sv, unsigned int& max_value)
+{
+    TBM_bvector candidates;
+    const TBM_bvector* bv_null = sv.get_null_bvector();
+    if (bv_null)
+        candidates = *bv_null;
+    else if (sv.size())
+        candidates.set_range(0, sv.size() - 1);
+    if (!candidates.any())
+        return false;
+
+    max_value = 0;
+    for (unsigned int i = sizeof(unsigned int) * 8; i-- > 0;)
+    {
+        const TBM_bvector* plane = sv.get_plane(i);
+        if (!plane)
+            continue;
+
+        TBM_bvector narrowed(candidates);
+        narrowed.bit_and(*plane);
+        if (narrowed.any())
+        {
+            candidates.swap(narrowed);
+            max_value |= 1u << i;
+        }
+    }
+    return true;
+}
+
+int BM_sparse_vector_u32_set2set_transform(void* h, void* hbv_in, void* hbv_out)
+{
+    if (!h || !hbv_in || !hbv_out)
+        return BM_ERR_BADARG;
+
+    BM_EXT_TRY
+    {
+        const TBM_sparse_vector_u32* sv = (const TBM_sparse_vector_u32*)h;
+        TBM_bvector* bv_out = (TBM_bvector*)hbv_out;
+
+        // only the not-NULL elements hold a mapping
+        TBM_bvector bv_ids(*(const TBM_bvector*)hbv_in);
+        const TBM_bvector* bv_null = sv->get_null_bvector();
+        if (bv_null)
+            bv_ids.bit_and(*bv_null);
+
+        bv_out->clear();
+        bm::set2set_11_transform<TBM_sparse_vector_u32> transform;
+        transform.run(bv_ids, *sv, *bv_out);
+
+        // the ids of the result are values of sv
+        unsigned int max_value;
+        if (sv_u32_max(*sv, max_value) && max_value < bm::id_max - 1)
+            bv_out->resize(max_value + 1);
+    }
+    BM_EXT_CATCH_ALL
+
+    return BM_OK;
+}
+
+int BM_sparse_vector_u32_set2set_inverse(void* h, void* hbv_in, void* hbv_out)
+{
+    if (!h || !hbv_in || !hbv_out)
+        return BM_ERR_BADARG;
+
+    BM_EXT_TRY
+    {
+        const TBM_sparse_vector_u32* sv = (const TBM_sparse_vector_u32*)h;
+        const TBM_bvector* bv_in = (const TBM_bvector*)hbv_in;
+        TBM_bvector* bv_out = (TBM_bvector*)hbv_out;
+        bv_out->clear();
+
+        // decode every element once, a batch at a time, and test its value
+        // against bv_in: this is a full O(size) pass over sv, not an
+        // operation on its compressed form
+        const unsigned int batch = 1024;
+        unsigned int values[batch];
+        // 64-bit, so it doesn't wrap past the last batch
+        const unsigned long long size = sv->size();
+        for (unsigned long long from = 0; from < size; from += batch)
+        {
+            unsigned int decoded =
+                sv->decode(values, TBM_sparse_vector_u32::size_type(from), batch);
+            for (unsigned int i = 0; i < decoded; ++i)
+            {
+                if (bv_in->test(values[i]))
+                    bv_out->set_bit_no_check(TBM_bvector::size_type(from + i));
+            }
+        }
+        // NULL elements are decoded as 0
+        sv_finish_result(*sv, *bv_out);
+    }
+    BM_EXT_CATCH_ALL
+
+    return BM_OK;
+}
+
+// ------------------------------------------------------------------
+// str_sparse_vector search
+// ------------------------------------------------------------------
+
+int BM_str_sparse_vector_scanner_construct(void** hsc)
+{
+    return ext_construct<TBM_str_sv_scanner>(hsc);
+}
+
+int BM_str_sparse_vector_scanner_free(void* hsc)
+{
+    return ext_free<TBM_str_sv_scanner>(hsc);
+}
+
+int BM_str_sparse_vector_scanner_bind(void* hsc, void* h, int sorted)
+{
+    if (!hsc || !h)
+        return BM_ERR_BADARG;
+
+    BM_EXT_TRY
+    {
+        TBM_str_sv_scanner* sc = (TBM_str_sv_scanner*)hsc;
+        sc->sv = (const TBM_str_sparse_vector*)h;
+        sc->scanner.bind(*sc->sv, bool(sorted));
+    }
+    BM_EXT_CATCH_ALL
+
+    return BM_OK;
+}
+
+int BM_str_sparse_vector_scanner_find_eq(void* hsc, const char* str, void* hbv)
+{
+    if (!hsc || !str || !hbv)
+        return BM_ERR_BADARG;
+
+    TBM_str_sv_scanner* sc = (TBM_str_sv_scanner*)hsc;
+    if (!sc->sv)
+        return BM_ERR_BADARG;
+
+    BM_EXT_TRY
+    {
+        TBM_bvector* bv = (TBM_bvector*)hbv;
+        bv->clear();
+        sc->scanner.find_eq_str(*sc->sv, str, *bv);
+        sv_finish_result(*sc->sv, *bv);
+    }
+    BM_EXT_CATCH_ALL
+
+    return BM_OK;
+}
+
+int BM_str_sparse_vector_scanner_find_prefix(void* hsc, const char* str, void* hbv)
+{
+    if (!hsc || !str || !hbv)
+        return BM_ERR_BADARG;
+
+    TBM_str_sv_scanner* sc = (TBM_str_sv_scanner*)hsc;
+    if (!sc->sv)
+        return BM_ERR_BADARG;
+
+    BM_EXT_TRY
+    {
+        TBM_bvector* bv = (TBM_bvector*)hbv;
+        bv->clear();
+        if (*str)
+        {
+            sc->scanner.find_eq_str_prefix(*sc->sv, str, *bv);
+        }
+        else if (sc->sv->size())
+        {
+            // every string starts with the empty prefix
+            bv->set_range(0, sc->sv->size() - 1);
+        }
+        sv_finish_result(*sc->sv, *bv);
+    }
+    BM_EXT_CATCH_ALL
+
+    return BM_OK;
+}
+
+int BM_str_sparse_vector_scanner_lower_bound(void* hsc,
+                                             const char* str,
+                                             unsigned int* ppos,
+                                             int* pfound)
+{
+    if (!hsc || !str || !ppos || !pfound)
+        return BM_ERR_BADARG;
+
+    TBM_str_sv_scanner* sc = (TBM_str_sv_scanner*)hsc;
+    if (!sc->sv)
+        return BM_ERR_BADARG;
+
+    BM_EXT_TRY
+    {
+        TBM_str_sparse_vector::size_type pos = 0;
+        *pfound = sc->scanner.lower_bound_str(*sc->sv, str, pos);
+        *ppos = pos;
+    }
+    BM_EXT_CATCH_ALL
+
+    return BM_OK;
+}
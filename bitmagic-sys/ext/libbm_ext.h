@@ -0,0 +1,366 @@
+#ifndef LIBBM_EXT_INCLUDED_H__
+#define LIBBM_EXT_INCLUDED_H__
+
+/*
+ Extensions to the libbm C API, for BitMagic functionality that is not
+ exposed by lang-maps/libbm but is needed by the Rust bindings.
+
+ Follows the libbm conventions: every function returns a BM_* error code,
+ and objects are passed around as opaque handles.
+*/
+
+#include <stddef.h>
+
+#include "libbm.h"
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+/* ------------------------------------------------------------------ */
+/* bvector word import/export                                          */
+/* ------------------------------------------------------------------ */
+
+/* replace the content of a bvector with the bits of a raw 32-bit word array
+   (bit i is bit (i % 32) of words[i / 32]); returns BM_ERR_RANGE if a bit
+   past the addressable ones is enabled */
+int BM_bvector_import_u32(void* h,
+                          const unsigned int* words,
+                          size_t word_count);
+
+/* export bits [0, word_count * 32) of a bvector into a raw 32-bit word
+   array, a block at a time */
+int BM_bvector_export_u32(void* h,
+                          unsigned int* words,
+                          size_t word_count);
+
+/* export the positions of the enabled bits of a bvector, in increasing order.
+   At most ids_size positions are written, and *pcount is set to the number
+   of positions written */
+int BM_bvector_export_ids(void* h,
+                          unsigned int* ids,
+                          size_t ids_size,
+                          size_t* pcount);
+
+/* export the runs (intervals) of enabled bits of a bvector, starting from
+   position from. Each run is written as two elements of runs: its first and
+   its last (inclusive) position. At most runs_size runs are written, and
+   *pcount is set to the number of runs written */
+int BM_bvector_export_runs(void* h,
+                           unsigned int from,
+                           unsigned int* runs,
+                           size_t runs_size,
+                           size_t* pcount);
+
+/* find the first disabled bit of a bvector at or after position from,
+   regardless of its size; *pfound is set to 0 if there is none */
+int BM_bvector_find_zero(void* h,
+                         unsigned int from,
+                         unsigned int* ppos,
+                         int* pfound);
+
+/* The following functions move bits within [0, size) of a bvector, and
+   keep its size: bits moved past the end are dropped. */
+
+/* move every bit n positions towards the end (bit i becomes bit i + n) */
+int BM_bvector_shift_right(void* h, unsigned int n);
+
+/* move every bit n positions towards the start (bit i becomes bit i - n) */
+int BM_bvector_shift_left(void* h, unsigned int n);
+
+/* insert a bit with the given value at position pos, moving the bits from
+   pos onwards one position towards the end */
+int BM_bvector_insert_bit(void* h, unsigned int pos, int value);
+
+/* remove the bit at position pos, moving the bits after it one position
+   towards the start */
+int BM_bvector_erase_bit(void* h, unsigned int pos);
+
+/* replace the bvector hdst by the bits of hsrc in [left, right], with the
+   size of hsrc; blocks outside of the range are not copied */
+int BM_bvector_copy_range(void* hdst,
+                          void* hsrc,
+                          unsigned int left,
+                          unsigned int right);
+
+/* disable every bit outside of [left, right], freeing the blocks */
+int BM_bvector_keep_range(void* h, unsigned int left, unsigned int right);
+
+/* ------------------------------------------------------------------ */
+/* bvector rank-select index                                           */
+/* ------------------------------------------------------------------ */
+
+int BM_rs_index_construct(void** h);
+int BM_rs_index_free(void* h);
+
+/* (re)build the rank-select index hrs for the bvector h */
+int BM_bvector_build_rs_index(void* h, void* hrs);
+
+/* count enabled bits in [0, right] using a rank-select index */
+int BM_bvector_count_to_rs(void* h,
+                           void* hrs,
+                           unsigned int right,
+                           unsigned int* pcount);
+
+/* count enabled bits in [left, right] using a rank-select index */
+int BM_bvector_count_range_rs(void* h,
+                              void* hrs,
+                              unsigned int left,
+                              unsigned int right,
+                              unsigned int* pcount);
+
+/* find the position of the rank-th enabled bit (rank starts from 1)
+   using a rank-select index */
+int BM_bvector_select_rs(void* h,
+                         void* hrs,
+                         unsigned int rank,
+                         unsigned int* ppos,
+                         int* pfound);
+
+/* ------------------------------------------------------------------ */
+/* sparse_vector                                                       */
+/* ------------------------------------------------------------------ */
+
+/* Sparse vectors are bm::sparse_vector<> of unsigned 32-bit (u32) or
+   64-bit (u64) values, always created with NULL support. Each function
+   below exists in a _u32 and a _u64 flavour, with value type
+   unsigned int or unsigned long long respectively. */
+
+/* release a buffer allocated by the extension API */
+int BM_ext_buffer_free(void* buf);
+
+#define BM_EXT_SPARSE_VECTOR_DECL(SUFFIX, VALUE)                              \
+    int BM_sparse_vector_##SUFFIX##_construct(void** h);                      \
+    int BM_sparse_vector_##SUFFIX##_construct_copy(void** h, void* hfrom);    \
+    int BM_sparse_vector_##SUFFIX##_free(void* h);                            \
+    int BM_sparse_vector_##SUFFIX##_size(void* h, unsigned int* psize);       \
+    /* append a value */                                                      \
+    int BM_sparse_vector_##SUFFIX##_push_back(void* h, VALUE value);          \
+    /* set a value, growing the vector (with NULLs) if needed */              \
+    int BM_sparse_vector_##SUFFIX##_set(void* h,                              \
+                                        unsigned int idx,                     \
+                                        VALUE value);                         \
+    /* set a value to NULL, growing the vector (with NULLs) if needed */      \
+    int BM_sparse_vector_##SUFFIX##_set_null(void* h, unsigned int idx);      \
+    /* get a value; NULL values are reported with *pnull = 1 and *pvalue = 0, \
+       positions past the end of the vector fail with BM_ERR_RANGE */         \
+    int BM_sparse_vector_##SUFFIX##_get(void* h,                              \
+                                        unsigned int idx,                     \
+                                        VALUE* pvalue,                        \
+                                        int* pnull);                          \
+    /* set arr_size values starting from position offset */                   \
+    int BM_sparse_vector_##SUFFIX##_import(void* h,                           \
+                                           const VALUE* arr,                  \
+                                           size_t arr_size,                   \
+                                           unsigned int offset);              \
+    /* decode up to size values starting from position from (NULLs are        \
+       decoded as 0), *pdecoded is set to the number of values decoded */     \
+    int BM_sparse_vector_##SUFFIX##_decode(void* h,                           \
+                                           VALUE* arr,                        \
+                                           unsigned int from,                 \
+                                           size_t size,                       \
+                                           size_t* pdecoded);                 \
+    int BM_sparse_vector_##SUFFIX##_optimize(void* h);                        \
+    /* serialize into a new buffer, to be released with BM_ext_buffer_free */ \
+    int BM_sparse_vector_##SUFFIX##_serialize(void* h,                        \
+                                              unsigned char** pbuf,           \
+                                              size_t* psize);                 \
+    int BM_sparse_vector_##SUFFIX##_deserialize(void* h,                      \
+                                                const unsigned char* buf,     \
+                                                size_t buf_size);             \
+    /* borrow the NULL bvector (enabled bits are the not-NULL positions);     \
+       the handle is owned by the sparse vector and must not be freed */      \
+    int BM_sparse_vector_##SUFFIX##_get_null_bvector(void* h, void** pbv);    \
+    /* borrow bit-plane i of the values, or 0 if no value has that bit set;   \
+       the handle is owned by the sparse vector and must not be freed */      \
+    int BM_sparse_vector_##SUFFIX##_get_plane(void* h,                        \
+                                              unsigned int i,                 \
+                                              void** pbv);
+
+BM_EXT_SPARSE_VECTOR_DECL(u32, unsigned int)
+BM_EXT_SPARSE_VECTOR_DECL(u64, unsigned long long)
+
+/* ------------------------------------------------------------------ */
+/* rsc_sparse_vector                                                   */
+/* ------------------------------------------------------------------ */
+
+/* Succinct (rank-select compressed) sparse vectors, bm::rsc_sparse_vector<>
+   of the sparse vectors above. They only store the not-NULL values, and are
+   read-only once built: values are appended with push_back(), then sync()
+   builds the rank-select index used by get() and decode(). */
+
+#define BM_EXT_RSC_SPARSE_VECTOR_DECL(SUFFIX, VALUE)                          \
+    int BM_rsc_sparse_vector_##SUFFIX##_construct(void** h);                  \
+    int BM_rsc_sparse_vector_##SUFFIX##_construct_copy(void** h, void* hfrom);\
+    int BM_rsc_sparse_vector_##SUFFIX##_free(void* h);                        \
+    int BM_rsc_sparse_vector_##SUFFIX##_size(void* h, unsigned int* psize);   \
+    /* set the value at idx, which must not be before the end of the vector; \
+       the positions in between are NULL */                                   \
+    int BM_rsc_sparse_vector_##SUFFIX##_push_back(void* h,                    \
+                                                  unsigned int idx,           \
+                                                  VALUE value);               \
+    /* build the rank-select index after push_back() */                       \
+    int BM_rsc_sparse_vector_##SUFFIX##_sync(void* h);                        \
+    /* replace the content with the one of a sparse vector (hsv), and sync */ \
+    int BM_rsc_sparse_vector_##SUFFIX##_load_from(void* h, void* hsv);        \
+    /* decompress into a sparse vector (hsv) */                               \
+    int BM_rsc_sparse_vector_##SUFFIX##_load_to(void* h, void* hsv);          \
+    /* same as BM_sparse_vector_*_get */                                      \
+    int BM_rsc_sparse_vector_##SUFFIX##_get(void* h,                          \
+                                            unsigned int idx,                 \
+                                            VALUE* pvalue,                    \
+                                            int* pnull);                      \
+    /* same as BM_sparse_vector_*_decode */                                   \
+    int BM_rsc_sparse_vector_##SUFFIX##_decode(void* h,                       \
+                                               VALUE* arr,                    \
+                                               unsigned int from,             \
+                                               size_t size,                   \
+                                               size_t* pdecoded);             \
+    int BM_rsc_sparse_vector_##SUFFIX##_optimize(void* h);                    \
+    /* serialize into a new buffer, to be released with BM_ext_buffer_free */ \
+    int BM_rsc_sparse_vector_##SUFFIX##_serialize(void* h,                    \
+                                                  unsigned char** pbuf,       \
+                                                  size_t* psize);             \
+    int BM_rsc_sparse_vector_##SUFFIX##_deserialize(void* h,                  \
+                                                    const unsigned char* buf, \
+                                                    size_t buf_size);         \
+    /* borrow the not-NULL bvector, owned by the rsc vector */                \
+    int BM_rsc_sparse_vector_##SUFFIX##_get_null_bvector(void* h, void** pbv);
+
+BM_EXT_RSC_SPARSE_VECTOR_DECL(u32, unsigned int)
+BM_EXT_RSC_SPARSE_VECTOR_DECL(u64, unsigned long long)
+
+/* ------------------------------------------------------------------ */
+/* str_sparse_vector                                                   */
+/* ------------------------------------------------------------------ */
+
+/* String vectors are bm::str_sparse_vector<char> created with NULL
+   support, holding NUL-terminated strings shorter than
+   BM_STR_SPARSE_VECTOR_MAX_STR bytes. */
+
+#define BM_STR_SPARSE_VECTOR_MAX_STR 256
+
+int BM_str_sparse_vector_construct(void** h);
+int BM_str_sparse_vector_construct_copy(void** h, void* hfrom);
+int BM_str_sparse_vector_free(void* h);
+int BM_str_sparse_vector_size(void* h, unsigned int* psize);
+
+/* length of the longest string in the vector */
+int BM_str_sparse_vector_max_str_len(void* h, size_t* plen);
+
+/* append a string; remapped vectors are un-mapped first if the string has
+   characters outside of their remapping */
+int BM_str_sparse_vector_push_back(void* h, const char* str);
+
+/* set a string, growing the vector (with NULLs) if needed; remapped vectors
+   are un-mapped first if the string has characters outside of their
+   remapping */
+int BM_str_sparse_vector_set(void* h, unsigned int idx, const char* str);
+
+/* copy a string into buf (truncated to buf_size - 1 bytes); NULL values are
+   reported with *pnull = 1 and an empty string, positions past the end of
+   the vector fail with BM_ERR_RANGE */
+int BM_str_sparse_vector_get(void* h,
+                             unsigned int idx,
+                             char* buf,
+                             size_t buf_size,
+                             int* pnull);
+
+/* remap the characters of the vector to save memory; the vector stays
+   remapped until a string with other characters is stored */
+int BM_str_sparse_vector_remap(void* h);
+
+int BM_str_sparse_vector_optimize(void* h);
+int BM_str_sparse_vector_calc_stat(void* h, struct BM_bvector_statistics* pstat);
+
+/* serialize into a new buffer, to be released with BM_ext_buffer_free */
+int BM_str_sparse_vector_serialize(void* h, unsigned char** pbuf, size_t* psize);
+int BM_str_sparse_vector_deserialize(void* h, const unsigned char* buf, size_t buf_size);
+
+/* borrow the NULL bvector (enabled bits are the not-NULL positions) */
+int BM_str_sparse_vector_get_null_bvector(void* h, void** pbv);
+
+/* iterate over the strings of the vector h from position from; the vector
+   must not be modified while the iterator is in use */
+int BM_str_sparse_vector_iterator_construct(void** hit, void* h, unsigned int from);
+int BM_str_sparse_vector_iterator_free(void* hit);
+
+/* copy the current string into buf, like BM_str_sparse_vector_get, and
+   advance; *pvalid is set to 0 past the end of the vector */
+int BM_str_sparse_vector_iterator_next(void* hit,
+                                       char* buf,
+                                       size_t buf_size,
+                                       int* pnull,
+                                       int* pvalid);
+
+/* ------------------------------------------------------------------ */
+/* sparse_vector search                                                */
+/* ------------------------------------------------------------------ */
+
+/* Search a sparse vector h (see above), storing the positions of the
+   matching elements in the bvector hbv. NULL elements never match. */
+
+#define BM_EXT_SPARSE_VECTOR_SEARCH_DECL(SUFFIX, VALUE)                       \
+    /* elements equal to value */                                             \
+    int BM_sparse_vector_##SUFFIX##_find_eq(void* h, VALUE value, void* hbv); \
+    /* elements in [from, to] */                                              \
+    int BM_sparse_vector_##SUFFIX##_find_range(void* h,                       \
+                                               VALUE from,                    \
+                                               VALUE to,                      \
+                                               void* hbv);                    \
+    /* elements equal to any of values */                                     \
+    int BM_sparse_vector_##SUFFIX##_find_any_of(void* h,                      \
+                                                const VALUE* values,          \
+                                                size_t values_size,           \
+                                                void* hbv);                   \
+    /* NULL elements */                                                       \
+    int BM_sparse_vector_##SUFFIX##_find_null(void* h, void* hbv);
+
+BM_EXT_SPARSE_VECTOR_SEARCH_DECL(u32, unsigned int)
+BM_EXT_SPARSE_VECTOR_SEARCH_DECL(u64, unsigned long long)
+
+/* translate the ids in the bvector hbv_in through the 1:1 mapping held by
+   the sparse vector h (id i becomes the value of element i), storing the
+   result in the bvector hbv_out, sized to one past the largest value of h;
+   NULL elements and ids past the end of h have no mapping and are dropped */
+int BM_sparse_vector_u32_set2set_transform(void* h, void* hbv_in, void* hbv_out);
+
+/* inverse of BM_sparse_vector_u32_set2set_transform: store in the bvector
+   hbv_out the positions of the not-NULL elements of h whose value is in the
+   bvector hbv_in, all of them when several elements hold the same value */
+int BM_sparse_vector_u32_set2set_inverse(void* h, void* hbv_in, void* hbv_out);
+
+/* ------------------------------------------------------------------ */
+/* str_sparse_vector search                                            */
+/* ------------------------------------------------------------------ */
+
+/* A scanner searches the string vector it is bound to, which must not be
+   modified or freed while the scanner is in use. Searches are
+   case-sensitive, and NULL elements never match. */
+
+int BM_str_sparse_vector_scanner_construct(void** hsc);
+int BM_str_sparse_vector_scanner_free(void* hsc);
+
+/* bind the scanner to the vector h; if sorted is not 0 the vector must be
+   sorted, and an index is built to speed up
+   BM_str_sparse_vector_scanner_lower_bound */
+int BM_str_sparse_vector_scanner_bind(void* hsc, void* h, int sorted);
+
+/* store the positions of the strings equal to str in the bvector hbv */
+int BM_str_sparse_vector_scanner_find_eq(void* hsc, const char* str, void* hbv);
+
+/* store the positions of the strings starting with str in the bvector hbv */
+int BM_str_sparse_vector_scanner_find_prefix(void* hsc, const char* str, void* hbv);
+
+/* binary search in a sorted vector: *ppos is set to the position of the
+   first string not less than str, and *pfound to 1 if it is equal to str */
+int BM_str_sparse_vector_scanner_lower_bound(void* hsc,
+                                             const char* str,
+                                             unsigned int* ppos,
+                                             int* pfound);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif
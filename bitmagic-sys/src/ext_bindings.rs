@@ -0,0 +1,687 @@
+/* bindings for ext/libbm_ext.h, in the same format rust-bindgen generates */
+
+extern "C" {
+    pub fn BM_bvector_import_u32(
+        h: *mut ::core::ffi::c_void,
+        words: *const ::std::os::raw::c_uint,
+        word_count: usize,
+    ) -> ::std::os::raw::c_int;
+}
+extern "C" {
+    pub fn BM_bvector_export_u32(
+        h: *mut ::core::ffi::c_void,
+        words: *mut ::std::os::raw::c_uint,
+        word_count: usize,
+    ) -> ::std::os::raw::c_int;
+}
+extern "C" {
+    pub fn BM_bvector_export_ids(
+        h: *mut ::core::ffi::c_void,
+        ids: *mut ::std::os::raw::c_uint,
+        ids_size: usize,
+        pcount: *mut usize,
+    ) -> ::std::os::raw::c_int;
+}
+extern "C" {
+    pub fn BM_bvector_export_runs(
+        h: *mut ::core::ffi::c_void,
+        from: ::std::os::raw::c_uint,
+        runs: *mut ::std::os::raw::c_uint,
+        runs_size: usize,
+        pcount: *mut usize,
+    ) -> ::std::os::raw::c_int;
+}
+extern "C" {
+    pub fn BM_bvector_find_zero(
+        h: *mut ::core::ffi::c_void,
+        from: ::std::os::raw::c_uint,
+        ppos: *mut ::std::os::raw::c_uint,
+        pfound: *mut ::std::os::raw::c_int,
+    ) -> ::std::os::raw::c_int;
+}
+extern "C" {
+    pub fn BM_bvector_shift_right(
+        h: *mut ::core::ffi::c_void,
+        n: ::std::os::raw::c_uint,
+    ) -> ::std::os::raw::c_int;
+}
+extern "C" {
+    pub fn BM_bvector_shift_left(
+        h: *mut ::core::ffi::c_void,
+        n: ::std::os::raw::c_uint,
+    ) -> ::std::os::raw::c_int;
+}
+extern "C" {
+    pub fn BM_bvector_insert_bit(
+        h: *mut ::core::ffi::c_void,
+        pos: ::std::os::raw::c_uint,
+        value: ::std::os::raw::c_int,
+    ) -> ::std::os::raw::c_int;
+}
+extern "C" {
+    pub fn BM_bvector_erase_bit(
+        h: *mut ::core::ffi::c_void,
+        pos: ::std::os::raw::c_uint,
+    ) -> ::std::os::raw::c_int;
+}
+extern "C" {
+    pub fn BM_bvector_copy_range(
+        hdst: *mut ::core::ffi::c_void,
+        hsrc: *mut ::core::ffi::c_void,
+        left: ::std::os::raw::c_uint,
+        right: ::std::os::raw::c_uint,
+    ) -> ::std::os::raw::c_int;
+}
+extern "C" {
+    pub fn BM_bvector_keep_range(
+        h: *mut ::core::ffi::c_void,
+        left: ::std::os::raw::c_uint,
+        right: ::std::os::raw::c_uint,
+    ) -> ::std::os::raw::c_int;
+}
+extern "C" {
+    pub fn BM_rs_index_construct(h: *mut *mut ::core::ffi::c_void) -> ::std::os::raw::c_int;
+}
+extern "C" {
+    pub fn BM_rs_index_free(h: *mut ::core::ffi::c_void) -> ::std::os::raw::c_int;
+}
+extern "C" {
+    pub fn BM_bvector_build_rs_index(
+        h: *mut ::core::ffi::c_void,
+        hrs: *mut ::core::ffi::c_void,
+    ) -> ::std::os::raw::c_int;
+}
+extern "C" {
+    pub fn BM_bvector_count_to_rs(
+        h: *mut ::core::ffi::c_void,
+        hrs: *mut ::core::ffi::c_void,
+        right: ::std::os::raw::c_uint,
+        pcount: *mut ::std::os::raw::c_uint,
+    ) -> ::std::os::raw::c_int;
+}
+extern "C" {
+    pub fn BM_bvector_count_range_rs(
+        h: *mut ::core::ffi::c_void,
+        hrs: *mut ::core::ffi::c_void,
+        left: ::std::os::raw::c_uint,
+        right: ::std::os::raw::c_uint,
+        pcount: *mut ::std::os::raw::c_uint,
+    ) -> ::std::os::raw::c_int;
+}
+extern "C" {
+    pub fn BM_bvector_select_rs(
+        h: *mut ::core::ffi::c_void,
+        hrs: *mut ::core::ffi::c_void,
+        rank: ::std::os::raw::c_uint,
+        ppos: *mut ::std::os::raw::c_uint,
+        pfound: *mut ::std::os::raw::c_int,
+    ) -> ::std::os::raw::c_int;
+}
+extern "C" {
+    pub fn BM_ext_buffer_free(buf: *mut ::core::ffi::c_void) -> ::std::os::raw::c_int;
+}
+extern "C" {
+    pub fn BM_sparse_vector_u32_construct(
+        h: *mut *mut ::core::ffi::c_void,
+    ) -> ::std::os::raw::c_int;
+}
+extern "C" {
+    pub fn BM_sparse_vector_u32_construct_copy(
+        h: *mut *mut ::core::ffi::c_void,
+        hfrom: *mut ::core::ffi::c_void,
+    ) -> ::std::os::raw::c_int;
+}
+extern "C" {
+    pub fn BM_sparse_vector_u32_free(h: *mut ::core::ffi::c_void) -> ::std::os::raw::c_int;
+}
+extern "C" {
+    pub fn BM_sparse_vector_u32_size(
+        h: *mut ::core::ffi::c_void,
+        psize: *mut ::std::os::raw::c_uint,
+    ) -> ::std::os::raw::c_int;
+}
+extern "C" {
+    pub fn BM_sparse_vector_u32_push_back(
+        h: *mut ::core::ffi::c_void,
+        value: ::std::os::raw::c_uint,
+    ) -> ::std::os::raw::c_int;
+}
+extern "C" {
+    pub fn BM_sparse_vector_u32_set(
+        h: *mut ::core::ffi::c_void,
+        idx: ::std::os::raw::c_uint,
+        value: ::std::os::raw::c_uint,
+    ) -> ::std::os::raw::c_int;
+}
+extern "C" {
+    pub fn BM_sparse_vector_u32_set_null(
+        h: *mut ::core::ffi::c_void,
+        idx: ::std::os::raw::c_uint,
+    ) -> ::std::os::raw::c_int;
+}
+extern "C" {
+    pub fn BM_sparse_vector_u32_get(
+        h: *mut ::core::ffi::c_void,
+        idx: ::std::os::raw::c_uint,
+        pvalue: *mut ::std::os::raw::c_uint,
+        pnull: *mut ::std::os::raw::c_int,
+    ) -> ::std::os::raw::c_int;
+}
+extern "C" {
+    pub fn BM_sparse_vector_u32_import(
+        h: *mut ::core::ffi::c_void,
+        arr: *const ::std::os::raw::c_uint,
+        arr_size: usize,
+        offset: ::std::os::raw::c_uint,
+    ) -> ::std::os::raw::c_int;
+}
+extern "C" {
+    pub fn BM_sparse_vector_u32_decode(
+        h: *mut ::core::ffi::c_void,
+        arr: *mut ::std::os::raw::c_uint,
+        from: ::std::os::raw::c_uint,
+        size: usize,
+        pdecoded: *mut usize,
+    ) -> ::std::os::raw::c_int;
+}
+extern "C" {
+    pub fn BM_sparse_vector_u32_optimize(h: *mut ::core::ffi::c_void) -> ::std::os::raw::c_int;
+}
+extern "C" {
+    pub fn BM_sparse_vector_u32_serialize(
+        h: *mut ::core::ffi::c_void,
+        pbuf: *mut *mut ::std::os::raw::c_uchar,
+        psize: *mut usize,
+    ) -> ::std::os::raw::c_int;
+}
+extern "C" {
+    pub fn BM_sparse_vector_u32_deserialize(
+        h: *mut ::core::ffi::c_void,
+        buf: *const ::std::os::raw::c_uchar,
+        buf_size: usize,
+    ) -> ::std::os::raw::c_int;
+}
+extern "C" {
+    pub fn BM_sparse_vector_u32_get_null_bvector(
+        h: *mut ::core::ffi::c_void,
+        pbv: *mut *mut ::core::ffi::c_void,
+    ) -> ::std::os::raw::c_int;
+}
+extern "C" {
+    pub fn BM_sparse_vector_u32_get_plane(
+        h: *mut ::core::ffi::c_void,
+        i: ::std::os::raw::c_uint,
+        pbv: *mut *mut ::core::ffi::c_void,
+    ) -> ::std::os::raw::c_int;
+}
+extern "C" {
+    pub fn BM_sparse_vector_u64_construct(
+        h: *mut *mut ::core::ffi::c_void,
+    ) -> ::std::os::raw::c_int;
+}
+extern "C" {
+    pub fn BM_sparse_vector_u64_construct_copy(
+        h: *mut *mut ::core::ffi::c_void,
+        hfrom: *mut ::core::ffi::c_void,
+    ) -> ::std::os::raw::c_int;
+}
+extern "C" {
+    pub fn BM_sparse_vector_u64_free(h: *mut ::core::ffi::c_void) -> ::std::os::raw::c_int;
+}
+extern "C" {
+    pub fn BM_sparse_vector_u64_size(
+        h: *mut ::core::ffi::c_void,
+        psize: *mut ::std::os::raw::c_uint,
+    ) -> ::std::os::raw::c_int;
+}
+extern "C" {
+    pub fn BM_sparse_vector_u64_push_back(
+        h: *mut ::core::ffi::c_void,
+        value: ::std::os::raw::c_ulonglong,
+    ) -> ::std::os::raw::c_int;
+}
+extern "C" {
+    pub fn BM_sparse_vector_u64_set(
+        h: *mut ::core::ffi::c_void,
+        idx: ::std::os::raw::c_uint,
+        value: ::std::os::raw::c_ulonglong,
+    ) -> ::std::os::raw::c_int;
+}
+extern "C" {
+    pub fn BM_sparse_vector_u64_set_null(
+        h: *mut ::core::ffi::c_void,
+        idx: ::std::os::raw::c_uint,
+    ) -> ::std::os::raw::c_int;
+}
+extern "C" {
+    pub fn BM_sparse_vector_u64_get(
+        h: *mut ::core::ffi::c_void,
+        idx: ::std::os::raw::c_uint,
+        pvalue: *mut ::std::os::raw::c_ulonglong,
+        pnull: *mut ::std::os::raw::c_int,
+    ) -> ::std::os::raw::c_int;
+}
+extern "C" {
+    pub fn BM_sparse_vector_u64_import(
+        h: *mut ::core::ffi::c_void,
+        arr: *const ::std::os::raw::c_ulonglong,
+        arr_size: usize,
+        offset: ::std::os::raw::c_uint,
+    ) -> ::std::os::raw::c_int;
+}
+extern "C" {
+    pub fn BM_sparse_vector_u64_decode(
+        h: *mut ::core::ffi::c_void,
+        arr: *mut ::std::os::raw::c_ulonglong,
+        from: ::std::os::raw::c_uint,
+        size: usize,
+        pdecoded: *mut usize,
+    ) -> ::std::os::raw::c_int;
+}
+extern "C" {
+    pub fn BM_sparse_vector_u64_optimize(h: *mut ::core::ffi::c_void) -> ::std::os::raw::c_int;
+}
+extern "C" {
+    pub fn BM_sparse_vector_u64_serialize(
+        h: *mut ::core::ffi::c_void,
+        pbuf: *mut *mut ::std::os::raw::c_uchar,
+        psize: *mut usize,
+    ) -> ::std::os::raw::c_int;
+}
+extern "C" {
+    pub fn BM_sparse_vector_u64_deserialize(
+        h: *mut ::core::ffi::c_void,
+        buf: *const ::std::os::raw::c_uchar,
+        buf_size: usize,
+    ) -> ::std::os::raw::c_int;
+}
+extern "C" {
+    pub fn BM_sparse_vector_u64_get_null_bvector(
+        h: *mut ::core::ffi::c_void,
+        pbv: *mut *mut ::core::ffi::c_void,
+    ) -> ::std::os::raw::c_int;
+}
+extern "C" {
+    pub fn BM_sparse_vector_u64_get_plane(
+        h: *mut ::core::ffi::c_void,
+        i: ::std::os::raw::c_uint,
+        pbv: *mut *mut ::core::ffi::c_void,
+    ) -> ::std::os::raw::c_int;
+}
+extern "C" {
+    pub fn BM_rsc_sparse_vector_u32_construct(
+        h: *mut *mut ::core::ffi::c_void,
+    ) -> ::std::os::raw::c_int;
+}
+extern "C" {
+    pub fn BM_rsc_sparse_vector_u32_construct_copy(
+        h: *mut *mut ::core::ffi::c_void,
+        hfrom: *mut ::core::ffi::c_void,
+    ) -> ::std::os::raw::c_int;
+}
+extern "C" {
+    pub fn BM_rsc_sparse_vector_u32_free(h: *mut ::core::ffi::c_void) -> ::std::os::raw::c_int;
+}
+extern "C" {
+    pub fn BM_rsc_sparse_vector_u32_size(
+        h: *mut ::core::ffi::c_void,
+        psize: *mut ::std::os::raw::c_uint,
+    ) -> ::std::os::raw::c_int;
+}
+extern "C" {
+    pub fn BM_rsc_sparse_vector_u32_push_back(
+        h: *mut ::core::ffi::c_void,
+        idx: ::std::os::raw::c_uint,
+        value: ::std::os::raw::c_uint,
+    ) -> ::std::os::raw::c_int;
+}
+extern "C" {
+    pub fn BM_rsc_sparse_vector_u32_sync(h: *mut ::core::ffi::c_void) -> ::std::os::raw::c_int;
+}
+extern "C" {
+    pub fn BM_rsc_sparse_vector_u32_load_from(
+        h: *mut ::core::ffi::c_void,
+        hsv: *mut ::core::ffi::c_void,
+    ) -> ::std::os::raw::c_int;
+}
+extern "C" {
+    pub fn BM_rsc_sparse_vector_u32_load_to(
+        h: *mut ::core::ffi::c_void,
+        hsv: *mut ::core::ffi::c_void,
+    ) -> ::std::os::raw::c_int;
+}
+extern "C" {
+    pub fn BM_rsc_sparse_vector_u32_get(
+        h: *mut ::core::ffi::c_void,
+        idx: ::std::os::raw::c_uint,
+        pvalue: *mut ::std::os::raw::c_uint,
+        pnull: *mut ::std::os::raw::c_int,
+    ) -> ::std::os::raw::c_int;
+}
+extern "C" {
+    pub fn BM_rsc_sparse_vector_u32_decode(
+        h: *mut ::core::ffi::c_void,
+        arr: *mut ::std::os::raw::c_uint,
+        from: ::std::os::raw::c_uint,
+        size: usize,
+        pdecoded: *mut usize,
+    ) -> ::std::os::raw::c_int;
+}
+extern "C" {
+    pub fn BM_rsc_sparse_vector_u32_optimize(h: *mut ::core::ffi::c_void) -> ::std::os::raw::c_int;
+}
+extern "C" {
+    pub fn BM_rsc_sparse_vector_u32_serialize(
+        h: *mut ::core::ffi::c_void,
+        pbuf: *mut *mut ::std::os::raw::c_uchar,
+        psize: *mut usize,
+    ) -> ::std::os::raw::c_int;
+}
+extern "C" {
+    pub fn BM_rsc_sparse_vector_u32_deserialize(
+        h: *mut ::core::ffi::c_void,
+        buf: *const ::std::os::raw::c_uchar,
+        buf_size: usize,
+    ) -> ::std::os::raw::c_int;
+}
+extern "C" {
+    pub fn BM_rsc_sparse_vector_u32_get_null_bvector(
+        h: *mut ::core::ffi::c_void,
+        pbv: *mut *mut ::core::ffi::c_void,
+    ) -> ::std::os::raw::c_int;
+}
+extern "C" {
+    pub fn BM_rsc_sparse_vector_u64_construct(
+        h: *mut *mut ::core::ffi::c_void,
+    ) -> ::std::os::raw::c_int;
+}
+extern "C" {
+    pub fn BM_rsc_sparse_vector_u64_construct_copy(
+        h: *mut *mut ::core::ffi::c_void,
+        hfrom: *mut ::core::ffi::c_void,
+    ) -> ::std::os::raw::c_int;
+}
+extern "C" {
+    pub fn BM_rsc_sparse_vector_u64_free(h: *mut ::core::ffi::c_void) -> ::std::os::raw::c_int;
+}
+extern "C" {
+    pub fn BM_rsc_sparse_vector_u64_size(
+        h: *mut ::core::ffi::c_void,
+        psize: *mut ::std::os::raw::c_uint,
+    ) -> ::std::os::raw::c_int;
+}
+extern "C" {
+    pub fn BM_rsc_sparse_vector_u64_push_back(
+        h: *mut ::core::ffi::c_void,
+        idx: ::std::os::raw::c_uint,
+        value: ::std::os::raw::c_ulonglong,
+    ) -> ::std::os::raw::c_int;
+}
+extern "C" {
+    pub fn BM_rsc_sparse_vector_u64_sync(h: *mut ::core::ffi::c_void) -> ::std::os::raw::c_int;
+}
+extern "C" {
+    pub fn BM_rsc_sparse_vector_u64_load_from(
+        h: *mut ::core::ffi::c_void,
+        hsv: *mut ::core::ffi::c_void,
+    ) -> ::std::os::raw::c_int;
+}
+extern "C" {
+    pub fn BM_rsc_sparse_vector_u64_load_to(
+        h: *mut ::core::ffi::c_void,
+        hsv: *mut ::core::ffi::c_void,
+    ) -> ::std::os::raw::c_int;
+}
+extern "C" {
+    pub fn BM_rsc_sparse_vector_u64_get(
+        h: *mut ::core::ffi::c_void,
+        idx: ::std::os::raw::c_uint,
+        pvalue: *mut ::std::os::raw::c_ulonglong,
+        pnull: *mut ::std::os::raw::c_int,
+    ) -> ::std::os::raw::c_int;
+}
+extern "C" {
+    pub fn BM_rsc_sparse_vector_u64_decode(
+        h: *mut ::core::ffi::c_void,
+        arr: *mut ::std::os::raw::c_ulonglong,
+        from: ::std::os::raw::c_uint,
+        size: usize,
+        pdecoded: *mut usize,
+    ) -> ::std::os::raw::c_int;
+}
+extern "C" {
+    pub fn BM_rsc_sparse_vector_u64_optimize(h: *mut ::core::ffi::c_void) -> ::std::os::raw::c_int;
+}
+extern "C" {
+    pub fn BM_rsc_sparse_vector_u64_serialize(
+        h: *mut ::core::ffi::c_void,
+        pbuf: *mut *mut ::std::os::raw::c_uchar,
+        psize: *mut usize,
+    ) -> ::std::os::raw::c_int;
+}
+extern "C" {
+    pub fn BM_rsc_sparse_vector_u64_deserialize(
+        h: *mut ::core::ffi::c_void,
+        buf: *const ::std::os::raw::c_uchar,
+        buf_size: usize,
+    ) -> ::std::os::raw::c_int;
+}
+extern "C" {
+    pub fn BM_rsc_sparse_vector_u64_get_null_bvector(
+        h: *mut ::core::ffi::c_void,
+        pbv: *mut *mut ::core::ffi::c_void,
+    ) -> ::std::os::raw::c_int;
+}
+pub const BM_STR_SPARSE_VECTOR_MAX_STR: u32 = 256;
+extern "C" {
+    pub fn BM_str_sparse_vector_construct(
+        h: *mut *mut ::core::ffi::c_void,
+    ) -> ::std::os::raw::c_int;
+}
+extern "C" {
+    pub fn BM_str_sparse_vector_construct_copy(
+        h: *mut *mut ::core::ffi::c_void,
+        hfrom: *mut ::core::ffi::c_void,
+    ) -> ::std::os::raw::c_int;
+}
+extern "C" {
+    pub fn BM_str_sparse_vector_free(h: *mut ::core::ffi::c_void) -> ::std::os::raw::c_int;
+}
+extern "C" {
+    pub fn BM_str_sparse_vector_size(
+        h: *mut ::core::ffi::c_void,
+        psize: *mut ::std::os::raw::c_uint,
+    ) -> ::std::os::raw::c_int;
+}
+extern "C" {
+    pub fn BM_str_sparse_vector_max_str_len(
+        h: *mut ::core::ffi::c_void,
+        plen: *mut usize,
+    ) -> ::std::os::raw::c_int;
+}
+extern "C" {
+    pub fn BM_str_sparse_vector_push_back(
+        h: *mut ::core::ffi::c_void,
+        str_: *const ::std::os::raw::c_char,
+    ) -> ::std::os::raw::c_int;
+}
+extern "C" {
+    pub fn BM_str_sparse_vector_set(
+        h: *mut ::core::ffi::c_void,
+        idx: ::std::os::raw::c_uint,
+        str_: *const ::std::os::raw::c_char,
+    ) -> ::std::os::raw::c_int;
+}
+extern "C" {
+    pub fn BM_str_sparse_vector_get(
+        h: *mut ::core::ffi::c_void,
+        idx: ::std::os::raw::c_uint,
+        buf: *mut ::std::os::raw::c_char,
+        buf_size: usize,
+        pnull: *mut ::std::os::raw::c_int,
+    ) -> ::std::os::raw::c_int;
+}
+extern "C" {
+    pub fn BM_str_sparse_vector_remap(h: *mut ::core::ffi::c_void) -> ::std::os::raw::c_int;
+}
+extern "C" {
+    pub fn BM_str_sparse_vector_optimize(h: *mut ::core::ffi::c_void) -> ::std::os::raw::c_int;
+}
+extern "C" {
+    pub fn BM_str_sparse_vector_calc_stat(
+        h: *mut ::core::ffi::c_void,
+        pstat: *mut BM_bvector_statistics,
+    ) -> ::std::os::raw::c_int;
+}
+extern "C" {
+    pub fn BM_str_sparse_vector_serialize(
+        h: *mut ::core::ffi::c_void,
+        pbuf: *mut *mut ::std::os::raw::c_uchar,
+        psize: *mut usize,
+    ) -> ::std::os::raw::c_int;
+}
+extern "C" {
+    pub fn BM_str_sparse_vector_deserialize(
+        h: *mut ::core::ffi::c_void,
+        buf: *const ::std::os::raw::c_uchar,
+        buf_size: usize,
+    ) -> ::std::os::raw::c_int;
+}
+extern "C" {
+    pub fn BM_str_sparse_vector_get_null_bvector(
+        h: *mut ::core::ffi::c_void,
+        pbv: *mut *mut ::core::ffi::c_void,
+    ) -> ::std::os::raw::c_int;
+}
+extern "C" {
+    pub fn BM_str_sparse_vector_iterator_construct(
+        hit: *mut *mut ::core::ffi::c_void,
+        h: *mut ::core::ffi::c_void,
+        from: ::std::os::raw::c_uint,
+    ) -> ::std::os::raw::c_int;
+}
+extern "C" {
+    pub fn BM_str_sparse_vector_iterator_free(
+        hit: *mut ::core::ffi::c_void,
+    ) -> ::std::os::raw::c_int;
+}
+extern "C" {
+    pub fn BM_str_sparse_vector_iterator_next(
+        hit: *mut ::core::ffi::c_void,
+        buf: *mut ::std::os::raw::c_char,
+        buf_size: usize,
+        pnull: *mut ::std::os::raw::c_int,
+        pvalid: *mut ::std::os::raw::c_int,
+    ) -> ::std::os::raw::c_int;
+}
+extern "C" {
+    pub fn BM_sparse_vector_u32_find_eq(
+        h: *mut ::core::ffi::c_void,
+        value: ::std::os::raw::c_uint,
+        hbv: *mut ::core::ffi::c_void,
+    ) -> ::std::os::raw::c_int;
+}
+extern "C" {
+    pub fn BM_sparse_vector_u32_find_range(
+        h: *mut ::core::ffi::c_void,
+        from: ::std::os::raw::c_uint,
+        to: ::std::os::raw::c_uint,
+        hbv: *mut ::core::ffi::c_void,
+    ) -> ::std::os::raw::c_int;
+}
+extern "C" {
+    pub fn BM_sparse_vector_u32_find_any_of(
+        h: *mut ::core::ffi::c_void,
+        values: *const ::std::os::raw::c_uint,
+        values_size: usize,
+        hbv: *mut ::core::ffi::c_void,
+    ) -> ::std::os::raw::c_int;
+}
+extern "C" {
+    pub fn BM_sparse_vector_u32_find_null(
+        h: *mut ::core::ffi::c_void,
+        hbv: *mut ::core::ffi::c_void,
+    ) -> ::std::os::raw::c_int;
+}
+extern "C" {
+    pub fn BM_sparse_vector_u64_find_eq(
+        h: *mut ::core::ffi::c_void,
+        value: ::std::os::raw::c_ulonglong,
+        hbv: *mut ::core::ffi::c_void,
+    ) -> ::std::os::raw::c_int;
+}
+extern "C" {
+    pub fn BM_sparse_vector_u64_find_range(
+        h: *mut ::core::ffi::c_void,
+        from: ::std::os::raw::c_ulonglong,
+        to: ::std::os::raw::c_ulonglong,
+        hbv: *mut ::core::ffi::c_void,
+    ) -> ::std::os::raw::c_int;
+}
+extern "C" {
+    pub fn BM_sparse_vector_u64_find_any_of(
+        h: *mut ::core::ffi::c_void,
+        values: *const ::std::os::raw::c_ulonglong,
+        values_size: usize,
+        hbv: *mut ::core::ffi::c_void,
+    ) -> ::std::os::raw::c_int;
+}
+extern "C" {
+    pub fn BM_sparse_vector_u64_find_null(
+        h: *mut ::core::ffi::c_void,
+        hbv: *mut ::core::ffi::c_void,
+    ) -> ::std::os::raw::c_int;
+}
+extern "C" {
+    pub fn BM_sparse_vector_u32_set2set_transform(
+        h: *mut ::core::ffi::c_void,
+        hbv_in: *mut ::core::ffi::c_void,
+        hbv_out: *mut ::core::ffi::c_void,
+    ) -> ::std::os::raw::c_int;
+}
+extern "C" {
+    pub fn BM_sparse_vector_u32_set2set_inverse(
+        h: *mut ::core::ffi::c_void,
+        hbv_in: *mut ::core::ffi::c_void,
+        hbv_out: *mut ::core::ffi::c_void,
+    ) -> ::std::os::raw::c_int;
+}
+extern "C" {
+    pub fn BM_str_sparse_vector_scanner_construct(
+        hsc: *mut *mut ::core::ffi::c_void,
+    ) -> ::std::os::raw::c_int;
+}
+extern "C" {
+    pub fn BM_str_sparse_vector_scanner_free(
+        hsc: *mut ::core::ffi::c_void,
+    ) -> ::std::os::raw::c_int;
+}
+extern "C" {
+    pub fn BM_str_sparse_vector_scanner_bind(
+        hsc: *mut ::core::ffi::c_void,
+        h: *mut ::core::ffi::c_void,
+        sorted: ::std::os::raw::c_int,
+    ) -> ::std::os::raw::c_int;
+}
+extern "C" {
+    pub fn BM_str_sparse_vector_scanner_find_eq(
+        hsc: *mut ::core::ffi::c_void,
+        str_: *const ::std::os::raw::c_char,
+        hbv: *mut ::core::ffi::c_void,
+    ) -> ::std::os::raw::c_int;
+}
+extern "C" {
+    pub fn BM_str_sparse_vector_scanner_find_prefix(
+        hsc: *mut ::core::ffi::c_void,
+        str_: *const ::std::os::raw::c_char,
+        hbv: *mut ::core::ffi::c_void,
+    ) -> ::std::os::raw::c_int;
+}
+extern "C" {
+    pub fn BM_str_sparse_vector_scanner_lower_bound(
+        hsc: *mut ::core::ffi::c_void,
+        str_: *const ::std::os::raw::c_char,
+        ppos: *mut ::std::os::raw::c_uint,
+        pfound: *mut ::std::os::raw::c_int,
+    ) -> ::std::os::raw::c_int;
+}
@@ -12,6 +12,9 @@ include!(concat!(env!("OUT_DIR"), "/bindings.rs"));
 #[cfg(not(feature = "bindgen"))]
 include!("bindings.rs");
 
+#[cfg(not(feature = "bindgen"))]
+include!("ext_bindings.rs");
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -0,0 +1,297 @@
+use std::collections::BTreeMap;
+use std::ptr;
+
+use crate::sparse_vector::sealed::RawValue;
+use crate::{_check_res, BVector, BVectorRef, SparseValue, SparseVector};
+
+// Groups this small are decoded row by row instead of being split further.
+const DECODE_GROUP_SIZE: usize = 16;
+
+/// Rows of a `SparseVector` grouped by value.
+pub(crate) enum Group<'a, T> {
+    /// The rows, and how many of them there are, all holding the value.
+    Equal(T, &'a BVector, usize),
+    /// A few decoded `(value, row)` pairs, in increasing order.
+    Decoded(&'a [(T, usize)]),
+}
+
+impl<T: SparseValue> SparseVector<T> {
+    /// Number of rows selected by `mask` that are not NULL.
+    pub fn count(&self, mask: &BVector) -> usize {
+        mask.intersection_count(&self.not_null())
+    }
+
+    /// Sum of the values selected by `mask`, skipping NULLs, or `None` if
+    /// all the selected rows are NULL.
+    ///
+    /// The sum is computed from the population count of every bit-plane
+    /// within the selection. It is exact: an `i128` holds the sum of any
+    /// number of values.
+    pub fn sum(&self, mask: &BVector) -> Option<i128> {
+        let rows = self.selected(mask);
+        let count = rows.count_all();
+        if count == 0 {
+            return None;
+        }
+
+        let mut sum = 0i128;
+        for i in 0..T::Raw::BITS {
+            if let Some(plane) = self.plane(i) {
+                sum += (rows.intersection_count(&plane) as i128) << i;
+            }
+        }
+
+        // signed values are offset so that the raw value 0 is their minimum
+        let offset: i128 = T::from_raw(T::Raw::default()).into();
+        Some(sum + offset * count as i128)
+    }
+
+    /// Smallest value selected by `mask`, skipping NULLs, or `None` if all
+    /// the selected rows are NULL.
+    pub fn min(&self, mask: &BVector) -> Option<T> {
+        self.extreme(mask, false)
+    }
+
+    /// Largest value selected by `mask`, skipping NULLs, or `None` if all
+    /// the selected rows are NULL.
+    pub fn max(&self, mask: &BVector) -> Option<T> {
+        self.extreme(mask, true)
+    }
+
+    /// Number of distinct values selected by `mask`, NULL excluded.
+    pub fn count_distinct(&self, mask: &BVector) -> usize {
+        self.histogram(mask).len()
+    }
+
+    /// Number of occurrences of every value selected by `mask`. NULL rows
+    /// are not counted, see [`count`] for the number of rows that are.
+    ///
+    /// The selection is split on the bit-planes, from the highest one, into
+    /// groups sharing the same leading bits. Only small groups are decoded.
+    ///
+    /// [`count`]: SparseVector::count
+    pub fn histogram(&self, mask: &BVector) -> BTreeMap<T, usize> {
+        let mut histogram = BTreeMap::new();
+
+        self.for_each_group(mask, |group| match group {
+            Group::Equal(value, _, count) => *histogram.entry(value).or_insert(0) += count,
+            Group::Decoded(pairs) => {
+                for &(value, _) in pairs {
+                    *histogram.entry(value).or_insert(0) += 1;
+                }
+            }
+        });
+
+        histogram
+    }
+
+    /// Split the not-NULL rows selected by `mask` by value, calling `f`
+    /// with the groups in increasing value order.
+    pub(crate) fn for_each_group<F>(&self, mask: &BVector, mut f: F)
+    where
+        F: FnMut(Group<'_, T>),
+    {
+        let rows = self.selected(mask);
+        let count = rows.count_all();
+        if count == 0 {
+            return;
+        }
+
+        let mut decoded = vec![];
+
+        // groups of rows whose values share the bits above `bits`; the
+        // group with the smaller values is pushed last, to be popped first
+        let mut groups = vec![(rows, count, T::Raw::BITS, 0u64)];
+        while let Some((mut rows, count, bits, prefix)) = groups.pop() {
+            if bits == 0 {
+                let value = T::from_raw(T::Raw::from_u64(prefix));
+                f(Group::Equal(value, &rows, count));
+                continue;
+            }
+            if count <= DECODE_GROUP_SIZE {
+                decoded.clear();
+                decoded.extend(
+                    rows.ones()
+                        .filter_map(|row| self.get(row).map(|value| (value, row))),
+                );
+                decoded.sort_unstable();
+                f(Group::Decoded(&decoded));
+                continue;
+            }
+
+            let i = bits - 1;
+            let plane = match self.plane(i) {
+                Some(plane) => plane,
+                None => {
+                    groups.push((rows, count, i, prefix));
+                    continue;
+                }
+            };
+
+            let ones = rows.intersection_count(&plane);
+            if ones == 0 {
+                groups.push((rows, count, i, prefix));
+            } else if ones == count {
+                groups.push((rows, count, i, prefix | 1 << i));
+            } else {
+                let mut one_rows = rows.clone();
+                one_rows.intersect_with(&plane);
+                rows.difference_with(&plane);
+
+                groups.push((one_rows, ones, i, prefix | 1 << i));
+                groups.push((rows, count - ones, i, prefix));
+            }
+        }
+    }
+
+    /// The not-NULL rows selected by `mask`.
+    fn selected(&self, mask: &BVector) -> BVector {
+        let mut rows = mask.clone();
+        rows.intersect_with(&self.not_null());
+        rows
+    }
+
+    /// Bit-plane `i` of the raw values, or `None` if no value has bit `i`
+    /// set. NULL rows have all their bits unset.
+    fn plane(&self, i: u32) -> Option<BVectorRef<'_>> {
+        let mut bv = ptr::null_mut();
+
+        let res;
+        unsafe {
+            res = T::Raw::get_plane(self.handle, i, &mut bv);
+        }
+        _check_res(res);
+
+        if bv.is_null() {
+            None
+        } else {
+            Some(unsafe { BVectorRef::from_handle(bv) })
+        }
+    }
+
+    /// Walk down the bit-planes, from the highest one, keeping the rows with
+    /// the largest (or smallest) bit at each step.
+    fn extreme(&self, mask: &BVector, largest: bool) -> Option<T> {
+        let mut rows = self.selected(mask);
+        let mut count = rows.count_all();
+        if count == 0 {
+            return None;
+        }
+
+        let mut raw = 0u64;
+        for i in (0..T::Raw::BITS).rev() {
+            let plane = match self.plane(i) {
+                Some(plane) => plane,
+                None => continue,
+            };
+
+            let ones = rows.intersection_count(&plane);
+            let keep_ones = if largest { ones > 0 } else { ones == count };
+            if keep_ones {
+                raw |= 1 << i;
+                if ones < count {
+                    rows.intersect_with(&plane);
+                    count = ones;
+                }
+            } else if ones > 0 {
+                rows.difference_with(&plane);
+                count -= ones;
+            }
+        }
+
+        Some(T::from_raw(T::Raw::from_u64(raw)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use crate::{BVector, SparseVector};
+
+    fn mask(rows: &[usize]) -> BVector {
+        let mut bv = BVector::new();
+        for &row in rows {
+            bv.insert(row);
+        }
+        bv
+    }
+
+    #[test]
+    fn masked_reductions() {
+        let mut sv = SparseVector::<u32>::new();
+        sv.import_from_slice(0, &[5, 3, 9, 3, 0, 7]);
+        sv.set_null(3);
+
+        let all = mask(&[0, 1, 2, 3, 4, 5, 100]);
+        assert_eq!(sv.count(&all), 5);
+        assert_eq!(sv.sum(&all), Some(24));
+        assert_eq!(sv.min(&all), Some(0));
+        assert_eq!(sv.max(&all), Some(9));
+
+        let some = mask(&[1, 3, 5]);
+        assert_eq!(sv.count(&some), 2);
+        assert_eq!(sv.sum(&some), Some(10));
+        assert_eq!(sv.min(&some), Some(3));
+        assert_eq!(sv.max(&some), Some(7));
+    }
+
+    #[test]
+    fn only_nulls() {
+        let mut sv = SparseVector::<u64>::new();
+        sv.push(1);
+        sv.set_null(5);
+
+        let nulls = mask(&[2, 3, 5, 6]);
+        assert_eq!(sv.count(&nulls), 0);
+        assert_eq!(sv.sum(&nulls), None);
+        assert_eq!(sv.min(&nulls), None);
+        assert_eq!(sv.max(&nulls), None);
+        assert_eq!(sv.count_distinct(&nulls), 0);
+        assert!(sv.histogram(&nulls).is_empty());
+    }
+
+    #[test]
+    fn signed_values() {
+        let mut sv = SparseVector::<i32>::new();
+        sv.import_from_slice(0, &[-7, 12, i32::MIN, -1, i32::MAX]);
+
+        let all = mask(&[0, 1, 2, 3, 4]);
+        assert_eq!(sv.sum(&all), Some(3));
+        assert_eq!(sv.min(&all), Some(i32::MIN));
+        assert_eq!(sv.max(&all), Some(i32::MAX));
+
+        let some = mask(&[0, 3]);
+        assert_eq!(sv.sum(&some), Some(-8));
+        assert_eq!(sv.min(&some), Some(-7));
+        assert_eq!(sv.max(&some), Some(-1));
+    }
+
+    #[test]
+    fn sum_does_not_overflow() {
+        let mut sv = SparseVector::<u64>::new();
+        sv.import_from_slice(0, &[u64::MAX; 3]);
+
+        let all = mask(&[0, 1, 2]);
+        assert_eq!(sv.sum(&all), Some(3 * u64::MAX as i128));
+    }
+
+    #[test]
+    fn histogram() {
+        let values: Vec<u32> = (0..10_000).map(|i| i % 7 * 1000 + i % 3).collect();
+
+        let mut sv = SparseVector::<u32>::new();
+        sv.import_from_slice(0, &values);
+        sv.set_null(0);
+
+        let rows = mask(&(0..5000).collect::<Vec<_>>());
+
+        let mut expected = BTreeMap::new();
+        for &value in &values[1..5000] {
+            *expected.entry(value).or_insert(0) += 1;
+        }
+
+        assert_eq!(sv.histogram(&rows), expected);
+        assert_eq!(sv.count_distinct(&rows), 21);
+    }
+}
@@ -0,0 +1,210 @@
+//! Interoperability with [Apache Arrow](https://arrow.apache.org/).
+//!
+//! A `BVector` maps naturally to Arrow bitmaps: bit `i` of the vector is
+//! row `i` of the array. Conversions are provided for [`BooleanBuffer`]
+//! (values), [`NullBuffer`] (validity, where an enabled bit means the row
+//! is valid) and [`UInt32Array`] (sorted row ids).
+//!
+//! [`filter`] applies a `BVector` as a row-selection mask to any Arrow
+//! array, going through the row ids instead of a dense boolean array.
+
+use std::convert::TryFrom;
+
+use arrow_array::{Array, ArrayRef, UInt32Array};
+use arrow_buffer::{BooleanBuffer, Buffer, NullBuffer};
+use arrow_schema::ArrowError;
+
+use crate::conversions::check_capacity;
+use crate::{_check_res, BVector, CapacityError};
+
+impl TryFrom<&BooleanBuffer> for BVector {
+    type Error = CapacityError;
+
+    fn try_from(bits: &BooleanBuffer) -> Result<Self, Self::Error> {
+        check_capacity(bits.len())?;
+
+        let chunks = bits.inner().bit_chunks(bits.offset(), bits.len());
+        let mut words = Vec::with_capacity((bits.len() + 31) / 32);
+        for chunk in chunks.iter_padded() {
+            words.push(chunk as u32);
+            words.push((chunk >> 32) as u32);
+        }
+        BVector::from_u32_words(&words, bits.len())
+    }
+}
+
+impl From<&BVector> for BooleanBuffer {
+    fn from(bv: &BVector) -> Self {
+        let len = bv.word_extent();
+
+        let words = bv.to_u32_words(len);
+        let mut bytes = Vec::with_capacity(words.len() * 4);
+        for word in words {
+            bytes.extend_from_slice(&word.to_le_bytes());
+        }
+
+        BooleanBuffer::new(Buffer::from_vec(bytes), 0, len)
+    }
+}
+
+impl TryFrom<&NullBuffer> for BVector {
+    type Error = CapacityError;
+
+    fn try_from(validity: &NullBuffer) -> Result<Self, Self::Error> {
+        BVector::try_from(validity.inner())
+    }
+}
+
+impl From<&BVector> for NullBuffer {
+    fn from(bv: &BVector) -> Self {
+        NullBuffer::new(BooleanBuffer::from(bv))
+    }
+}
+
+/// Row ids are loaded in a single call, null entries are skipped. The
+/// capacity of the vector ends after the largest id.
+impl TryFrom<&UInt32Array> for BVector {
+    type Error = CapacityError;
+
+    fn try_from(ids: &UInt32Array) -> Result<Self, Self::Error> {
+        let mut bv = BVector::with_capacity(0);
+
+        let load = |bv: &mut BVector, ids: &[u32]| -> Result<(), CapacityError> {
+            // BitMagic reserves u32::MAX, so the largest id is u32::MAX - 1
+            if let Some(&max) = ids.iter().max() {
+                check_capacity(max as usize + 1)?;
+                bv.grow(max as usize + 1);
+            }
+
+            let res;
+            unsafe {
+                res = bitmagic_sys::BM_bvector_combine_OR_arr(
+                    bv.handle,
+                    ids.as_ptr(),
+                    ids.as_ptr().add(ids.len()),
+                );
+            }
+            _check_res(res);
+            Ok(())
+        };
+
+        if ids.null_count() == 0 {
+            load(&mut bv, ids.values())?;
+        } else {
+            let valid: Vec<u32> = ids.iter().flatten().collect();
+            load(&mut bv, &valid)?;
+        }
+
+        Ok(bv)
+    }
+}
+
+/// The positions of the enabled bits, in increasing order.
+impl From<&BVector> for UInt32Array {
+    fn from(bv: &BVector) -> Self {
+        UInt32Array::from(bv.to_ids())
+    }
+}
+
+/// Select the rows of `array` enabled in `mask`.
+///
+/// Bits of `mask` past the end of `array` are ignored.
+pub fn filter(array: &dyn Array, mask: &BVector) -> Result<ArrayRef, ArrowError> {
+    let ids = mask
+        .ones()
+        .take_while(|&id| id < array.len())
+        .map(|id| id as u32);
+
+    arrow_select::take::take(array, &UInt32Array::from_iter_values(ids), None)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::convert::TryFrom;
+    use std::sync::Arc;
+
+    use arrow_array::{Array, Int64Array, StringArray, UInt32Array};
+    use arrow_buffer::{BooleanBuffer, NullBuffer};
+
+    use super::filter;
+    use crate::BVector;
+
+    #[test]
+    fn boolean_buffer_roundtrip() {
+        let bools: Vec<bool> = (0..100).map(|i| i % 3 == 0 || i == 99).collect();
+        let bits = BooleanBuffer::from(bools.as_slice());
+
+        let bv = BVector::try_from(&bits).unwrap();
+        assert_eq!(bv.len(), 100);
+        for (i, b) in bools.iter().enumerate() {
+            assert_eq!(bv.contains(i), *b);
+        }
+
+        assert_eq!(BooleanBuffer::from(&bv), bits);
+    }
+
+    #[test]
+    fn boolean_buffer_with_offset() {
+        let bools: Vec<bool> = (0..64).map(|i| i % 5 == 0).collect();
+        let bits = BooleanBuffer::from(bools.as_slice()).slice(3, 40);
+
+        let bv = BVector::try_from(&bits).unwrap();
+        assert_eq!(bv.len(), 40);
+        assert_eq!(
+            bv.ones().collect::<Vec<_>>(),
+            bits.set_indices().collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn null_buffer() {
+        let mut bv = BVector::with_capacity(10);
+        bv.insert_range(2..5);
+
+        let validity = NullBuffer::from(&bv);
+        assert_eq!(validity.len(), 10);
+        assert_eq!(validity.null_count(), 7);
+        assert!(validity.is_valid(3));
+
+        assert_eq!(BVector::try_from(&validity).unwrap(), bv);
+    }
+
+    #[test]
+    fn row_ids() {
+        let ids = UInt32Array::from(vec![Some(70), None, Some(3), Some(3), Some(12)]);
+
+        let bv = BVector::try_from(&ids).unwrap();
+        assert_eq!(bv.ones().collect::<Vec<_>>(), vec![3, 12, 70]);
+        assert_eq!(bv.len(), 71);
+        assert!(bv.contains(70));
+        assert_eq!(
+            BVector::try_from(&UInt32Array::from(vec![None]))
+                .unwrap()
+                .len(),
+            0
+        );
+
+        assert_eq!(UInt32Array::from(&bv), UInt32Array::from(vec![3, 12, 70]));
+    }
+
+    #[test]
+    fn filter_rows() {
+        let values: Arc<dyn Array> = Arc::new(Int64Array::from((0..10).collect::<Vec<i64>>()));
+        let names = StringArray::from(vec!["a", "b", "c"]);
+
+        let mut mask = BVector::new();
+        mask.extend(vec![1, 2, 7, 12]);
+
+        let filtered = filter(values.as_ref(), &mask).unwrap();
+        assert_eq!(
+            filtered.as_any().downcast_ref::<Int64Array>().unwrap(),
+            &Int64Array::from(vec![1, 2, 7])
+        );
+
+        let filtered = filter(&names, &mask).unwrap();
+        assert_eq!(
+            filtered.as_any().downcast_ref::<StringArray>().unwrap(),
+            &StringArray::from(vec!["b", "c"])
+        );
+    }
+}
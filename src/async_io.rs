@@ -0,0 +1,262 @@
+use std::future::Future;
+use std::io;
+
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+
+use crate::BVector;
+
+/// Number of bits serialized in each frame of an async stream.
+const FRAME_BITS: usize = 1 << 24;
+
+impl BVector {
+    /// Serialize bit vector into an async writer.
+    ///
+    /// The vector is written as a stream of frames, each one a serialized
+    /// slice of 2^24 bits, so it can be read back without buffering it
+    /// whole. The stream can only be read with [`deserialize_async`].
+    ///
+    /// The vector is serialized when this method is called, so the returned
+    /// future doesn't borrow `self` and can be sent to other tasks.
+    ///
+    /// [`deserialize_async`]: BVector::deserialize_async
+    pub fn serialize_async<'a, W>(&self, mut wtr: W) -> impl Future<Output = io::Result<()>> + 'a
+    where
+        W: AsyncWrite + Unpin + 'a,
+    {
+        let buf = self.serialize_frames();
+
+        async move {
+            wtr.write_all(&buf?).await?;
+            wtr.flush().await
+        }
+    }
+
+    /// Deserialize bit vector from an async reader, as written by
+    /// [`serialize_async`].
+    ///
+    /// Frames are deserialized as they arrive, so only one of them is
+    /// buffered at a time. At most `max_size` bytes are read: longer inputs
+    /// fail with [`io::ErrorKind::InvalidData`] before the frame crossing
+    /// the limit is read. Frames rejected by BitMagic fail with
+    /// [`io::ErrorKind::InvalidData`] too.
+    ///
+    /// [`serialize_async`]: BVector::serialize_async
+    pub async fn deserialize_async<R>(mut rdr: R, max_size: usize) -> io::Result<BVector>
+    where
+        R: AsyncRead + Unpin,
+    {
+        let mut budget = Budget {
+            remaining: max_size,
+            max_size,
+        };
+
+        let capacity = read_u32(&mut rdr, &mut budget).await?;
+
+        let mut bv = BVector::new();
+        let mut buf = vec![];
+        loop {
+            let frame_size = read_u32(&mut rdr, &mut budget).await? as usize;
+            if frame_size == 0 {
+                break;
+            }
+
+            budget.consume(frame_size)?;
+            buf.resize(frame_size, 0);
+            rdr.read_exact(&mut buf).await?;
+
+            bv.merge(BVector::deserialize_blob(&buf)?);
+        }
+
+        if capacity != 0 {
+            bv.grow(capacity as usize);
+        }
+        Ok(bv)
+    }
+
+    /// Serialize the vector as a stream of frames: its capacity (0 without
+    /// a fixed capacity), then the size and content of each non-empty
+    /// frame, and a size of 0 to end the stream. Integers are 32-bit little
+    /// endian.
+    fn serialize_frames(&self) -> io::Result<Vec<u8>> {
+        let mut buf = vec![];
+        buf.extend_from_slice(&(self.len() as u32).to_le_bytes());
+
+        let limit = self.bit_limit();
+        let mut start = 0;
+        while start < limit {
+            let end = start + (limit - start).min(FRAME_BITS);
+            let frame = self.copy_range(start..end);
+            start = end;
+
+            if frame.count_all() == 0 {
+                continue;
+            }
+
+            let blob = frame.serialize_to_vec()?;
+            buf.extend_from_slice(&(blob.len() as u32).to_le_bytes());
+            buf.extend_from_slice(&blob);
+        }
+
+        buf.extend_from_slice(&0u32.to_le_bytes());
+        Ok(buf)
+    }
+}
+
+/// Bytes left to read before reaching the size limit.
+struct Budget {
+    remaining: usize,
+    max_size: usize,
+}
+
+impl Budget {
+    fn consume(&mut self, size: usize) -> io::Result<()> {
+        if size > self.remaining {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!(
+                    "serialized BVector exceeds the {} bytes limit",
+                    self.max_size
+                ),
+            ));
+        }
+        self.remaining -= size;
+        Ok(())
+    }
+}
+
+async fn read_u32<R>(rdr: &mut R, budget: &mut Budget) -> io::Result<u32>
+where
+    R: AsyncRead + Unpin,
+{
+    let mut bytes = [0u8; 4];
+    budget.consume(bytes.len())?;
+    rdr.read_exact(&mut bytes).await?;
+    Ok(u32::from_le_bytes(bytes))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::future::Future;
+    use std::io;
+    use std::pin::Pin;
+    use std::task::{Context, Poll, RawWaker, RawWakerVTable, Waker};
+    use std::thread;
+
+    use super::FRAME_BITS;
+    use crate::BVector;
+
+    /// Run `fut` to completion on the current thread.
+    ///
+    /// The readers and writers used in the tests are always ready, so the
+    /// future never waits to be woken up.
+    fn block_on<F: Future>(mut fut: F) -> F::Output {
+        fn clone(_: *const ()) -> RawWaker {
+            RawWaker::new(std::ptr::null(), &VTABLE)
+        }
+        fn noop(_: *const ()) {}
+        static VTABLE: RawWakerVTable = RawWakerVTable::new(clone, noop, noop, noop);
+
+        let waker = unsafe { Waker::from_raw(clone(std::ptr::null())) };
+        let mut cx = Context::from_waker(&waker);
+
+        // the future is shadowed, so it can't be moved after being pinned
+        let mut fut = unsafe { Pin::new_unchecked(&mut fut) };
+        loop {
+            if let Poll::Ready(output) = fut.as_mut().poll(&mut cx) {
+                return output;
+            }
+        }
+    }
+
+    fn serialize(bv: &BVector) -> Vec<u8> {
+        let mut wtr = vec![];
+        block_on(bv.serialize_async(&mut wtr)).unwrap();
+        wtr
+    }
+
+    #[test]
+    fn serde_async() {
+        let mut bv = BVector::with_capacity(100);
+        bv.set_range(10..20, true);
+        bv.set_range(50..70, true);
+
+        let wtr = serialize(&bv);
+        let new_bv = block_on(BVector::deserialize_async(wtr.as_slice(), wtr.len())).unwrap();
+        assert_eq!(new_bv, bv);
+        assert_eq!(new_bv.len(), 100);
+    }
+
+    #[test]
+    fn many_frames() {
+        let mut bv = BVector::new();
+        for &bit in &[
+            5,
+            FRAME_BITS - 1,
+            FRAME_BITS,
+            3 * FRAME_BITS + 7,
+            4_000_000_000,
+        ] {
+            bv.insert(bit);
+        }
+
+        let wtr = serialize(&bv);
+        let new_bv = block_on(BVector::deserialize_async(wtr.as_slice(), wtr.len())).unwrap();
+        assert_eq!(
+            new_bv.ones().collect::<Vec<_>>(),
+            bv.ones().collect::<Vec<_>>()
+        );
+        assert_eq!(new_bv.len(), 0);
+
+        let empty = serialize(&BVector::new());
+        let new_bv = block_on(BVector::deserialize_async(empty.as_slice(), 8)).unwrap();
+        assert_eq!(new_bv.count_all(), 0);
+    }
+
+    #[test]
+    fn size_limit() {
+        let mut bv = BVector::with_capacity(100);
+        bv.set_range(10..20, true);
+        let wtr = serialize(&bv);
+
+        let err = block_on(BVector::deserialize_async(wtr.as_slice(), wtr.len() - 1)).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+
+        let new_bv = block_on(BVector::deserialize_async(wtr.as_slice(), usize::MAX)).unwrap();
+        assert_eq!(new_bv, bv);
+    }
+
+    #[test]
+    fn truncated() {
+        let mut bv = BVector::with_capacity(100);
+        bv.set_range(10..20, true);
+        let wtr = serialize(&bv);
+
+        let truncated = &wtr[..wtr.len() - 6];
+        let err = block_on(BVector::deserialize_async(truncated, wtr.len())).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::UnexpectedEof);
+    }
+
+    #[test]
+    fn sent_to_other_threads() {
+        fn send<F: Future + Send + 'static>(fut: F) -> F {
+            fut
+        }
+
+        let mut bv = BVector::new();
+        bv.insert(42);
+
+        let fut = send(bv.serialize_async(Vec::new()));
+        thread::spawn(move || block_on(fut))
+            .join()
+            .unwrap()
+            .unwrap();
+
+        let buf = serialize(&bv);
+        let fut = send(async move { BVector::deserialize_async(buf.as_slice(), 1024).await });
+        let new_bv = thread::spawn(move || block_on(fut))
+            .join()
+            .unwrap()
+            .unwrap();
+        assert_eq!(new_bv.ones().collect::<Vec<_>>(), vec![42]);
+    }
+}
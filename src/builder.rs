@@ -0,0 +1,155 @@
+use crate::{_check_res, BVector};
+
+// Ids buffered by a `BVectorBuilder` before they are sorted and loaded.
+const BATCH_SIZE: usize = 1 << 16;
+
+impl BVector {
+    /// Enable the bits in `ids`, which can be in any order and repeat.
+    ///
+    /// The ids are loaded in a single call, which is much faster than
+    /// inserting them one by one, and fastest when they are sorted. A vector
+    /// with a capacity grows to fit the largest id.
+    ///
+    /// **Panics** if an id is `u32::MAX`, which BitMagic reserves.
+    pub fn insert_many(&mut self, ids: &[u32]) {
+        let max = match ids.iter().max() {
+            Some(&max) => max,
+            None => return,
+        };
+        assert!(max != u32::MAX, "bit out of bounds: {}", max);
+
+        let len = self.len();
+        if len > 0 && max as usize >= len {
+            self.grow(max as usize + 1);
+        }
+
+        for chunk in ids.chunks(u32::MAX as usize) {
+            let res;
+            unsafe {
+                // the ids are only read, despite the mutable pointer
+                res = bitmagic_sys::BM_bvector_set_bits(
+                    self.handle,
+                    chunk.as_ptr() as *mut u32,
+                    chunk.len() as u32,
+                );
+            }
+            _check_res(res);
+        }
+    }
+}
+
+/// Bulk loader for a `BVector`.
+///
+/// The ids pushed into the builder are buffered, and every batch is sorted
+/// and loaded with [`insert_many`]. The [`Extend`] and [`FromIterator`]
+/// implementations of `BVector` go through a builder.
+///
+/// [`insert_many`]: BVector::insert_many
+pub struct BVectorBuilder {
+    bv: BVector,
+    batch: Vec<u32>,
+}
+
+impl BVectorBuilder {
+    /// Create a builder for a new vector without capacity.
+    pub fn new() -> BVectorBuilder {
+        BVectorBuilder::from(BVector::new())
+    }
+
+    /// Enable bit `id`.
+    ///
+    /// **Panics** if `id` is `u32::MAX`, which BitMagic reserves.
+    pub fn push(&mut self, id: u32) {
+        assert!(id != u32::MAX, "bit out of bounds: {}", id);
+
+        self.batch.push(id);
+        if self.batch.len() == BATCH_SIZE {
+            self.flush();
+        }
+    }
+
+    /// Load the buffered ids and return the vector.
+    pub fn finish(mut self) -> BVector {
+        self.flush();
+        self.bv
+    }
+
+    fn flush(&mut self) {
+        self.batch.sort_unstable();
+        self.bv.insert_many(&self.batch);
+        self.batch.clear();
+    }
+}
+
+impl Default for BVectorBuilder {
+    fn default() -> Self {
+        BVectorBuilder::new()
+    }
+}
+
+/// Continue loading an existing vector.
+impl From<BVector> for BVectorBuilder {
+    fn from(bv: BVector) -> Self {
+        BVectorBuilder {
+            bv,
+            batch: Vec::with_capacity(BATCH_SIZE),
+        }
+    }
+}
+
+impl Extend<u32> for BVectorBuilder {
+    fn extend<I: IntoIterator<Item = u32>>(&mut self, src: I) {
+        for id in src {
+            self.push(id);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{BVectorBuilder, BATCH_SIZE};
+    use crate::BVector;
+
+    #[test]
+    fn insert_many() {
+        let mut bv = BVector::new();
+        bv.insert_many(&[30, 2, 1_000_000, 2, 7]);
+        bv.insert_many(&[]);
+        assert_eq!(bv.ones().collect::<Vec<_>>(), vec![2, 7, 30, 1_000_000]);
+
+        let mut bounded = BVector::with_capacity(10);
+        bounded.insert_many(&[3, 20]);
+        assert_eq!(bounded.len(), 21);
+        assert!(bounded.contains(20));
+    }
+
+    #[should_panic]
+    #[test]
+    fn insert_many_reserved_id() {
+        BVector::new().insert_many(&[1, u32::MAX]);
+    }
+
+    #[test]
+    fn builder() {
+        let ids: Vec<u32> = (0..3 * BATCH_SIZE as u32).rev().map(|i| i * 3).collect();
+
+        let mut builder = BVectorBuilder::new();
+        builder.extend(ids.iter().cloned());
+        let bv = builder.finish();
+
+        assert_eq!(bv.count_all(), ids.len());
+        assert_eq!(bv.ones().take(3).collect::<Vec<_>>(), vec![0, 3, 6]);
+    }
+
+    #[test]
+    fn builder_from_bvector() {
+        let mut bv = BVector::new();
+        bv.insert(5);
+
+        let mut builder = BVectorBuilder::from(bv);
+        builder.push(1);
+        builder.push(5);
+
+        assert_eq!(builder.finish().ones().collect::<Vec<_>>(), vec![1, 5]);
+    }
+}
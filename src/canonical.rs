@@ -0,0 +1,130 @@
+use std::error::Error;
+use std::hash::{Hash, Hasher};
+use std::io::Write;
+
+use crate::{_check_res, BVector};
+
+const FNV_OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+const FNV_PRIME: u64 = 0x0000_0100_0000_01b3;
+
+impl BVector {
+    /// Serialize bit vector in canonical form.
+    ///
+    /// [`serialize`] writes the current block layout of the vector, which
+    /// depends on how it was built. The canonical form only depends on the
+    /// enabled bits and the capacity of the vector, so identical content
+    /// always produces identical bytes (for a given BitMagic version).
+    ///
+    /// The output is a regular serialized vector, and can be read back with
+    /// [`deserialize`].
+    ///
+    /// [`serialize`]: BVector::serialize
+    /// [`deserialize`]: BVector::deserialize
+    pub fn serialize_canonical<W>(&self, wtr: W) -> Result<(), Box<dyn Error>>
+    where
+        W: Write,
+    {
+        self.canonical_copy().serialize(wtr)
+    }
+
+    /// Hash of the enabled bits of the vector.
+    ///
+    /// The hash is consistent with `PartialEq`: the capacity and the block
+    /// layout of the vector are not included. It is computed by this crate
+    /// (64-bit FNV-1a over the runs of enabled bits), so it is stable across
+    /// platforms and versions, but it is not a cryptographic hash.
+    pub fn content_hash(&self) -> u64 {
+        let mut hash = FNV_OFFSET_BASIS;
+        self.for_each_run(|first, last| {
+            for byte in first.to_le_bytes().iter().chain(&last.to_le_bytes()) {
+                hash ^= u64::from(*byte);
+                hash = hash.wrapping_mul(FNV_PRIME);
+            }
+        });
+        hash
+    }
+
+    /// A copy of the vector built from scratch, one run at a time, so its
+    /// block layout only depends on its content.
+    fn canonical_copy(&self) -> BVector {
+        let copy = BVector::with_capacity(self.len());
+        self.for_each_run(|first, last| {
+            let res;
+            unsafe {
+                res = bitmagic_sys::BM_bvector_set_range(copy.handle, first, last, 1);
+            }
+            _check_res(res);
+        });
+        copy
+    }
+}
+
+impl Hash for BVector {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        state.write_u64(self.content_hash());
+    }
+}
+
+impl Eq for BVector {}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use crate::BVector;
+
+    #[test]
+    fn canonical_bytes() {
+        let mut a = BVector::with_capacity(1000);
+        a.set_range(10..20, true);
+        a.set_range(50..70, true);
+
+        let mut b = BVector::with_capacity(1000);
+        for i in (0..1000).rev() {
+            b.insert(i);
+        }
+        b.set_range(..10, false);
+        b.set_range(20..50, false);
+        b.set_range(70.., false);
+
+        assert_eq!(a, b);
+
+        let mut a_bytes = vec![];
+        a.serialize_canonical(&mut a_bytes).unwrap();
+        let mut b_bytes = vec![];
+        b.serialize_canonical(&mut b_bytes).unwrap();
+        assert_eq!(a_bytes, b_bytes);
+
+        let new_a = BVector::deserialize(a_bytes.as_slice()).unwrap();
+        assert_eq!(new_a, a);
+        assert_eq!(new_a.len(), a.len());
+    }
+
+    #[test]
+    fn content_hash() {
+        let mut a = BVector::with_capacity(100);
+        a.set_range(10..20, true);
+        a.set_range(50..70, true);
+
+        let mut b: BVector = (50..70).chain(10..20).collect();
+        assert_eq!(a.content_hash(), b.content_hash());
+
+        b.insert(99);
+        assert_ne!(a.content_hash(), b.content_hash());
+
+        let mut set = HashSet::new();
+        set.insert(a.clone());
+        assert!(!set.insert(a.clone()));
+        assert!(set.insert(b));
+    }
+
+    #[test]
+    fn content_hash_is_stable() {
+        assert_eq!(BVector::new().content_hash(), 0xcbf2_9ce4_8422_2325);
+
+        let mut bv = BVector::with_capacity(100);
+        bv.set_range(10..20, true);
+        bv.set_range(50..70, true);
+        assert_eq!(bv.content_hash(), 0x986a_0e38_f530_424b);
+    }
+}
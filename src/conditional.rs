@@ -0,0 +1,149 @@
+use crate::{_check_res, BVector};
+
+impl BVector {
+    /// Set `bit` to `new` if it is currently `expected`, returning whether
+    /// it was.
+    ///
+    /// **Panics** if **bit** is out of bounds.
+    pub fn compare_and_set(&mut self, bit: usize, new: bool, expected: bool) -> bool {
+        assert!(bit < self.bit_limit(), "bit out of bounds: {}", bit);
+        if new == expected {
+            return self.get_bit(bit) == expected;
+        }
+        self.set_bit_conditional(bit, new)
+    }
+
+    /// [`compare_and_set`] every bit of `bits` in turn, returning for each
+    /// of them whether it was `expected`.
+    ///
+    /// A bit repeated in `bits` only matches the first time, which makes
+    /// it a deduplication pass when setting bits that are expected disabled.
+    ///
+    /// **Panics** if a bit is out of bounds.
+    ///
+    /// [`compare_and_set`]: BVector::compare_and_set
+    pub fn compare_and_set_many(&mut self, bits: &[usize], new: bool, expected: bool) -> Vec<bool> {
+        bits.iter()
+            .map(|&bit| self.compare_and_set(bit, new, expected))
+            .collect()
+    }
+
+    /// Set `bit` to `enabled`, and return its previous value, in a single
+    /// call into BitMagic.
+    ///
+    /// **Panics** if **bit** is out of bounds.
+    pub fn replace(&mut self, bit: usize, enabled: bool) -> bool {
+        assert!(bit < self.bit_limit(), "bit out of bounds: {}", bit);
+        // the bit only changes if it had the other value
+        let changed = self.set_bit_conditional(bit, enabled);
+        changed != enabled
+    }
+
+    /// Value of `bit`, whether or not it is within the capacity.
+    fn get_bit(&self, bit: usize) -> bool {
+        let mut pval = 0;
+
+        let res;
+        unsafe {
+            res = bitmagic_sys::BM_bvector_get_bit(self.handle, bit as u32, &mut pval);
+        }
+        _check_res(res);
+
+        pval == 1
+    }
+
+    /// Set `bit` to `val` if it is `!val`, returning whether it changed.
+    ///
+    /// BitMagic grows the vector to hold `bit`, so it must be checked
+    /// against the capacity first.
+    fn set_bit_conditional(&mut self, bit: usize, val: bool) -> bool {
+        let mut pchanged = 0;
+
+        let res;
+        unsafe {
+            res = bitmagic_sys::BM_bvector_set_bit_conditional(
+                self.handle,
+                bit as u32,
+                val as i32,
+                !val as i32,
+                &mut pchanged,
+            );
+        }
+        _check_res(res);
+
+        pchanged != 0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::BVector;
+
+    #[test]
+    fn compare_and_set() {
+        let mut bv = BVector::with_capacity(10);
+
+        assert!(bv.compare_and_set(3, true, false));
+        assert!(bv.contains(3));
+        assert!(!bv.compare_and_set(3, true, false));
+
+        assert!(!bv.compare_and_set(4, false, true));
+        assert!(!bv.contains(4));
+        assert!(bv.compare_and_set(3, false, true));
+        assert!(!bv.contains(3));
+
+        assert!(bv.compare_and_set(5, false, false));
+        assert!(!bv.compare_and_set(5, true, true));
+        assert!(!bv.contains(5));
+        assert_eq!(bv.len(), 10);
+    }
+
+    #[test]
+    fn compare_and_set_unbounded() {
+        let mut bv = BVector::new();
+        bv.insert(3);
+
+        assert!(bv.compare_and_set(3, true, true));
+        assert!(bv.compare_and_set(4, false, false));
+        assert!(!bv.compare_and_set(4, true, true));
+        assert!(bv.compare_and_set(70_000, true, false));
+        assert_eq!(bv.ones().collect::<Vec<_>>(), vec![3, 70_000]);
+        assert_eq!(bv.len(), 0);
+    }
+
+    #[should_panic]
+    #[test]
+    fn compare_and_set_out_of_bounds() {
+        BVector::with_capacity(10).compare_and_set(10, true, false);
+    }
+
+    #[test]
+    fn compare_and_set_many() {
+        let mut bv = BVector::with_capacity(10);
+        bv.insert(2);
+
+        assert_eq!(
+            bv.compare_and_set_many(&[1, 2, 1, 7], true, false),
+            vec![true, false, false, true]
+        );
+        assert_eq!(bv.ones().collect::<Vec<_>>(), vec![1, 2, 7]);
+    }
+
+    #[test]
+    fn replace() {
+        let mut bv = BVector::with_capacity(10);
+
+        assert!(!bv.replace(1, true));
+        assert!(bv.replace(1, true));
+        assert!(bv.replace(1, false));
+        assert!(!bv.replace(1, false));
+        assert!(!bv.contains(1));
+        assert_eq!(bv.len(), 10);
+    }
+
+    #[should_panic]
+    #[test]
+    fn replace_out_of_bounds() {
+        BVector::with_capacity(10).replace(10, true);
+    }
+}
@@ -0,0 +1,436 @@
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+
+use crate::{_check_res, BVector};
+
+/// Maximum number of bits a `BVector` can hold.
+///
+/// BitMagic uses 32-bit addressing, and a size of `u32::MAX` is reserved
+/// for vectors created without an explicit capacity.
+pub(crate) const MAX_BITS: usize = u32::MAX as usize - 1;
+
+/// The error returned when converting a bitset that is too large to fit in
+/// a `BVector`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CapacityError {
+    bits: usize,
+}
+
+impl CapacityError {
+    /// Number of bits in the bitset that failed to convert.
+    pub fn bits(&self) -> usize {
+        self.bits
+    }
+}
+
+impl Display for CapacityError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "bitset with {} bits exceeds the maximum BVector capacity ({} bits)",
+            self.bits, MAX_BITS
+        )
+    }
+}
+
+impl Error for CapacityError {}
+
+pub(crate) fn check_capacity(bits: usize) -> Result<(), CapacityError> {
+    if bits > MAX_BITS {
+        Err(CapacityError { bits })
+    } else {
+        Ok(())
+    }
+}
+
+impl BVector {
+    /// Create a `BVector` from raw 64-bit words.
+    ///
+    /// Bit `i` of the vector is bit `i % 64` of `words[i / 64]`, and the
+    /// capacity of the new vector is `words.len() * 64` bits.
+    ///
+    /// **Panics** if the words hold more bits than a `BVector` can address.
+    pub fn from_words(words: &[u64]) -> BVector {
+        let mut halves = Vec::with_capacity(words.len() * 2);
+        for word in words {
+            halves.push(*word as u32);
+            halves.push((*word >> 32) as u32);
+        }
+        BVector::from_u32_words(&halves, words.len() * 64).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Create a `BVector` from raw bytes.
+    ///
+    /// Bit `i` of the vector is bit `i % 8` of `bytes[i / 8]`, and the
+    /// capacity of the new vector is `bytes.len() * 8` bits.
+    ///
+    /// **Panics** if the bytes hold more bits than a `BVector` can address.
+    pub fn from_bytes(bytes: &[u8]) -> BVector {
+        let mut words = Vec::with_capacity((bytes.len() + 3) / 4);
+        for chunk in bytes.chunks(4) {
+            let mut word = [0u8; 4];
+            word[..chunk.len()].copy_from_slice(chunk);
+            words.push(u32::from_le_bytes(word));
+        }
+        BVector::from_u32_words(&words, bytes.len() * 8).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Export the content of the `BVector` as raw 64-bit words, in the
+    /// layout accepted by [`from_words`].
+    ///
+    /// The words cover the capacity of the vector or, for vectors created
+    /// with [`new`], everything up to the last enabled bit.
+    ///
+    /// [`from_words`]: BVector::from_words
+    /// [`new`]: BVector::new
+    pub fn to_words(&self) -> Vec<u64> {
+        let bits = self.word_extent();
+        let halves = self.to_u32_words((bits + 63) / 64 * 64);
+        halves
+            .chunks(2)
+            .map(|pair| u64::from(pair[0]) | (u64::from(pair[1]) << 32))
+            .collect()
+    }
+
+    /// Build a vector with capacity `bits` out of 32-bit words, moving
+    /// whole blocks at a time.
+    pub(crate) fn from_u32_words(words: &[u32], bits: usize) -> Result<BVector, CapacityError> {
+        check_capacity(bits)?;
+        debug_assert!(words.len() * 32 >= bits);
+
+        let mut bv = BVector::with_capacity(bits);
+
+        let res;
+        unsafe {
+            res = bitmagic_sys::BM_bvector_import_u32(bv.handle, words.as_ptr(), words.len());
+        }
+        // the words have enabled bits past the addressable ones
+        if res == bitmagic_sys::BM_ERR_RANGE as i32 {
+            return Err(CapacityError {
+                bits: words.len() * 32,
+            });
+        }
+        _check_res(res);
+
+        bv.grow(bits);
+        Ok(bv)
+    }
+
+    /// Export the first `bits` bits of the vector as 32-bit words.
+    pub(crate) fn to_u32_words(&self, bits: usize) -> Vec<u32> {
+        let mut words = vec![0u32; (bits + 31) / 32];
+
+        let res;
+        unsafe {
+            res = bitmagic_sys::BM_bvector_export_u32(self.handle, words.as_mut_ptr(), words.len());
+        }
+        _check_res(res);
+
+        words
+    }
+
+    /// Export the positions of all enabled bits, in increasing order.
+    pub(crate) fn to_ids(&self) -> Vec<u32> {
+        let mut ids = vec![0u32; self.count_all()];
+        let mut count = 0;
+
+        let res;
+        unsafe {
+            res = bitmagic_sys::BM_bvector_export_ids(
+                self.handle,
+                ids.as_mut_ptr(),
+                ids.len(),
+                &mut count,
+            );
+        }
+        _check_res(res);
+
+        ids.truncate(count);
+        ids
+    }
+
+    /// Number of enabled bits, regardless of the vector capacity.
+    pub(crate) fn count_all(&self) -> usize {
+        let mut pcount = 0;
+
+        let res;
+        unsafe {
+            res = bitmagic_sys::BM_bvector_count(self.handle, &mut pcount);
+        }
+        _check_res(res);
+
+        pcount as usize
+    }
+
+    /// Call `f` with the first and last (inclusive) position of every run of
+    /// enabled bits, in increasing order.
+    pub(crate) fn for_each_run<F>(&self, mut f: F)
+    where
+        F: FnMut(u32, u32),
+    {
+        self.for_each_run_from(0, |first, last| {
+            f(first, last);
+            true
+        });
+    }
+
+    /// Call `f` with the first and last (inclusive) position of the runs of
+    /// enabled bits from position `from` onwards, in increasing order, until
+    /// it returns `false`. A run containing `from` is reported as starting
+    /// at `from`.
+    pub(crate) fn for_each_run_from<F>(&self, mut from: u32, mut f: F)
+    where
+        F: FnMut(u32, u32) -> bool,
+    {
+        const CHUNK: usize = 1024;
+
+        let mut runs = vec![0u32; 2 * CHUNK];
+        loop {
+            let mut count = 0;
+
+            let res;
+            unsafe {
+                res = bitmagic_sys::BM_bvector_export_runs(
+                    self.handle,
+                    from,
+                    runs.as_mut_ptr(),
+                    CHUNK,
+                    &mut count,
+                );
+            }
+            _check_res(res);
+
+            for run in runs[..2 * count].chunks(2) {
+                if !f(run[0].max(from), run[1]) {
+                    return;
+                }
+            }
+
+            if count < CHUNK || runs[2 * count - 1] as usize >= MAX_BITS - 1 {
+                break;
+            }
+            from = runs[2 * count - 1] + 1;
+        }
+    }
+
+    /// Number of bits the vector can hold: its capacity, or all the
+    /// addressable bits for vectors without a fixed capacity.
+    pub(crate) fn bit_limit(&self) -> usize {
+        let len = self.len();
+        if len == 0 {
+            MAX_BITS
+        } else {
+            len
+        }
+    }
+
+    /// Size of the vector as stored by BitMagic: its capacity, or
+    /// `u32::MAX` for vectors without a fixed capacity.
+    ///
+    /// Unlike [`bit_limit`], this is one past the last addressable bit for
+    /// vectors without a fixed capacity, so growing a vector to it leaves
+    /// the vector without a fixed capacity instead of fixing it to
+    /// `MAX_BITS` bits.
+    ///
+    /// [`bit_limit`]: BVector::bit_limit
+    pub(crate) fn raw_size(&self) -> usize {
+        match self.len() {
+            0 => u32::MAX as usize,
+            len => len,
+        }
+    }
+
+    /// Number of bits covered when exporting the vector: its capacity, or
+    /// one past the last enabled bit for vectors without a fixed capacity.
+    pub(crate) fn word_extent(&self) -> usize {
+        let len = self.len();
+        if len != 0 {
+            return len;
+        }
+
+        let mut pos = 0;
+        let mut found = 0;
+
+        let res;
+        unsafe {
+            res = bitmagic_sys::BM_bvector_find_reverse(self.handle, &mut pos, &mut found);
+        }
+        _check_res(res);
+
+        if found == 1 {
+            pos as usize + 1
+        } else {
+            0
+        }
+    }
+}
+
+#[cfg(feature = "fixedbitset")]
+impl std::convert::TryFrom<&fixedbitset::FixedBitSet> for BVector {
+    type Error = CapacityError;
+
+    fn try_from(bits: &fixedbitset::FixedBitSet) -> Result<Self, Self::Error> {
+        BVector::from_u32_words(bits.as_slice(), bits.len())
+    }
+}
+
+#[cfg(feature = "fixedbitset")]
+impl From<&BVector> for fixedbitset::FixedBitSet {
+    fn from(bv: &BVector) -> Self {
+        let len = bv.word_extent();
+        let mut bits = fixedbitset::FixedBitSet::with_capacity(len);
+        bits.as_mut_slice().copy_from_slice(&bv.to_u32_words(len));
+        bits
+    }
+}
+
+#[cfg(feature = "bit-vec")]
+impl std::convert::TryFrom<&bit_vec::BitVec> for BVector {
+    type Error = CapacityError;
+
+    fn try_from(bits: &bit_vec::BitVec) -> Result<Self, Self::Error> {
+        BVector::from_u32_words(bits.storage(), bits.len())
+    }
+}
+
+#[cfg(feature = "bit-vec")]
+impl From<&BVector> for bit_vec::BitVec {
+    fn from(bv: &BVector) -> Self {
+        let len = bv.word_extent();
+        let words = bv.to_u32_words(len);
+        let mut bits = bit_vec::BitVec::from_elem(len, false);
+        // `words` has exactly one block per 32 bits of `bits`, and no bits
+        // are enabled past `len`, so the `BitVec` invariants hold.
+        unsafe {
+            bits.storage_mut().copy_from_slice(&words);
+        }
+        bits
+    }
+}
+
+#[cfg(feature = "bitvec")]
+impl<T> std::convert::TryFrom<&bitvec::vec::BitVec<T, bitvec::order::Lsb0>> for BVector
+where
+    T: bitvec::store::BitStore,
+{
+    type Error = CapacityError;
+
+    fn try_from(bits: &bitvec::vec::BitVec<T, bitvec::order::Lsb0>) -> Result<Self, Self::Error> {
+        use bitvec::field::BitField;
+
+        check_capacity(bits.len())?;
+        let words: Vec<u32> = bits.chunks(32).map(|chunk| chunk.load_le()).collect();
+        BVector::from_u32_words(&words, bits.len())
+    }
+}
+
+#[cfg(feature = "bitvec")]
+impl<T> From<&BVector> for bitvec::vec::BitVec<T, bitvec::order::Lsb0>
+where
+    T: bitvec::store::BitStore,
+{
+    fn from(bv: &BVector) -> Self {
+        use bitvec::field::BitField;
+
+        let len = bv.word_extent();
+        let mut bits = bitvec::vec::BitVec::repeat(false, len);
+        for (chunk, word) in bits.chunks_mut(32).zip(bv.to_u32_words(len)) {
+            chunk.store_le(word);
+        }
+        bits
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::BVector;
+
+    #[test]
+    fn words_roundtrip() {
+        let words = vec![0x8000_0000_0000_0001u64, 0, u64::MAX, 0xdead_beef];
+        let bv = BVector::from_words(&words);
+
+        assert_eq!(bv.len(), 256);
+        assert!(bv.contains(0));
+        assert!(bv.contains(63));
+        assert!(!bv.contains(64));
+        assert_eq!(bv.count_ones(128..192), 64);
+        assert_eq!(bv.to_words(), words);
+    }
+
+    #[test]
+    fn bytes_layout() {
+        let bv = BVector::from_bytes(&[0b0000_0101, 0b1000_0000, 0xff]);
+
+        assert_eq!(bv.len(), 24);
+        assert_eq!(
+            bv.ones().collect::<Vec<_>>(),
+            vec![0, 2, 15, 16, 17, 18, 19, 20, 21, 22, 23]
+        );
+        assert_eq!(bv.to_words(), vec![0x00ff_8005]);
+    }
+
+    #[test]
+    fn to_words_without_capacity() {
+        let mut bv = BVector::new();
+        bv.insert(3);
+        bv.insert(70);
+
+        assert_eq!(bv.to_words(), vec![1 << 3, 1 << 6]);
+    }
+
+    #[cfg(feature = "fixedbitset")]
+    #[test]
+    fn fixedbitset_roundtrip() {
+        use fixedbitset::FixedBitSet;
+        use std::convert::TryFrom;
+
+        let mut fbs = FixedBitSet::with_capacity(100);
+        fbs.insert_range(10..20);
+        fbs.insert(99);
+
+        let bv = BVector::try_from(&fbs).unwrap();
+        assert_eq!(bv.len(), 100);
+        assert_eq!(
+            bv.ones().collect::<Vec<_>>(),
+            fbs.ones().collect::<Vec<_>>()
+        );
+
+        assert_eq!(FixedBitSet::from(&bv), fbs);
+    }
+
+    #[cfg(feature = "bit-vec")]
+    #[test]
+    fn bit_vec_roundtrip() {
+        use std::convert::TryFrom;
+
+        let mut bits = bit_vec::BitVec::from_elem(70, false);
+        bits.set(0, true);
+        bits.set(33, true);
+        bits.set(69, true);
+
+        let bv = BVector::try_from(&bits).unwrap();
+        assert_eq!(bv.len(), 70);
+        assert_eq!(bv.ones().collect::<Vec<_>>(), vec![0, 33, 69]);
+
+        assert_eq!(bit_vec::BitVec::from(&bv), bits);
+    }
+
+    #[cfg(feature = "bitvec")]
+    #[test]
+    fn bitvec_roundtrip() {
+        use bitvec::prelude::*;
+        use std::convert::TryFrom;
+
+        let mut bits = bitvec![u8, Lsb0; 0; 45];
+        bits.set(1, true);
+        bits.set(8, true);
+        bits.set(44, true);
+
+        let bv = BVector::try_from(&bits).unwrap();
+        assert_eq!(bv.len(), 45);
+        assert_eq!(bv.ones().collect::<Vec<_>>(), vec![1, 8, 44]);
+
+        assert_eq!(BitVec::<u8, Lsb0>::from(&bv), bits);
+    }
+}
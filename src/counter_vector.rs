@@ -0,0 +1,194 @@
+use crate::conversions::MAX_BITS;
+use crate::{_check_res, BVector};
+
+/// A vector of counters, one per bit position, stored as bit-planes.
+///
+/// Plane `p` holds bit `p` of every counter, so counters take the space of
+/// compressed `BVector`s and can be updated for a whole set of positions at
+/// once. Planes are added as the counters grow, so they never overflow.
+pub struct CounterVector {
+    planes: Vec<BVector>,
+}
+
+impl CounterVector {
+    /// Create a vector with all the counters at 0.
+    pub fn new() -> CounterVector {
+        CounterVector { planes: vec![] }
+    }
+
+    /// Increment the counter at position `i`.
+    ///
+    /// **Panics** if `i` is out of the addressable range.
+    pub fn increment(&mut self, i: usize) {
+        assert!(i < MAX_BITS, "position out of bounds: {}", i);
+
+        for plane in &mut self.planes {
+            let mut carry = 0;
+
+            let res;
+            unsafe {
+                res = bitmagic_sys::BM_bvector_inc_bit(plane.handle, i as u32, &mut carry);
+            }
+            _check_res(res);
+
+            if carry == 0 {
+                return;
+            }
+        }
+
+        let mut plane = BVector::new();
+        plane.insert(i);
+        self.planes.push(plane);
+    }
+
+    /// Increment the counters at the enabled positions of `bv`.
+    ///
+    /// The planes are updated with a ripple-carry addition of whole
+    /// vectors.
+    pub fn add_set(&mut self, bv: &BVector) {
+        // planes have no capacity, whatever the capacity of bv
+        let mut carry = BVector::new();
+        carry.union_with(bv);
+
+        for plane in &mut self.planes {
+            if carry.count_all() == 0 {
+                return;
+            }
+
+            let mut next_carry = plane.clone();
+            next_carry.intersect_with(&carry);
+            plane.symmetric_difference_with(&carry);
+            carry = next_carry;
+        }
+
+        if carry.count_all() > 0 {
+            self.planes.push(carry);
+        }
+    }
+
+    /// Value of the counter at position `i`.
+    pub fn get(&self, i: usize) -> u64 {
+        if i >= MAX_BITS {
+            return 0;
+        }
+
+        let mut count = 0;
+        for (p, plane) in self.planes.iter().enumerate() {
+            let mut pval = 0;
+
+            let res;
+            unsafe {
+                res = bitmagic_sys::BM_bvector_get_bit(plane.handle, i as u32, &mut pval);
+            }
+            _check_res(res);
+
+            count |= (pval as u64) << p;
+        }
+        count
+    }
+
+    /// The positions with a counter of at least `k`.
+    ///
+    /// The counters are compared with `k` plane by plane, from the highest
+    /// one, without being decoded.
+    ///
+    /// **Panics** if `k` is 0, as every position would match.
+    pub fn threshold(&self, k: u64) -> BVector {
+        assert!(k > 0, "threshold must be at least 1");
+
+        let bits = 64 - k.leading_zeros() as usize;
+        if bits > self.planes.len() {
+            return BVector::new();
+        }
+
+        // positions whose counter has the same leading bits as k, or larger
+        // ones; counters of 0 are never above a threshold of at least 1
+        let mut equal = BVector::new();
+        for plane in &self.planes {
+            equal.union_with(plane);
+        }
+        let mut greater = BVector::new();
+
+        for (p, plane) in self.planes.iter().enumerate().rev() {
+            if k >> p & 1 == 1 {
+                equal.intersect_with(plane);
+            } else {
+                let mut above = equal.clone();
+                above.intersect_with(plane);
+                greater.union_with(&above);
+                equal.difference_with(plane);
+            }
+        }
+
+        greater.union_with(&equal);
+        greater
+    }
+}
+
+impl Default for CounterVector {
+    fn default() -> Self {
+        CounterVector::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::CounterVector;
+    use crate::BVector;
+
+    fn ones(bv: &BVector) -> Vec<usize> {
+        bv.ones().collect()
+    }
+
+    #[test]
+    fn increment() {
+        let mut counters = CounterVector::new();
+        for _ in 0..5 {
+            counters.increment(10);
+        }
+        counters.increment(70_000);
+
+        assert_eq!(counters.get(10), 5);
+        assert_eq!(counters.get(70_000), 1);
+        assert_eq!(counters.get(11), 0);
+    }
+
+    #[test]
+    fn add_set() {
+        let mut counters = CounterVector::new();
+
+        for sample in 0..20 {
+            let mut bv = BVector::new();
+            for i in 0..10 {
+                // position i is in samples 0..2i
+                if sample < 2 * i {
+                    bv.insert(i);
+                }
+            }
+            counters.add_set(&bv);
+        }
+        counters.increment(9);
+
+        for i in 0..9 {
+            assert_eq!(counters.get(i), 2 * i as u64);
+        }
+        assert_eq!(counters.get(9), 19);
+    }
+
+    #[test]
+    fn threshold() {
+        let mut counters = CounterVector::new();
+        for (i, &count) in [0, 1, 2, 3, 4, 5, 6, 7, 8].iter().enumerate() {
+            for _ in 0..count {
+                counters.increment(i);
+            }
+        }
+
+        assert_eq!(ones(&counters.threshold(1)), vec![1, 2, 3, 4, 5, 6, 7, 8]);
+        assert_eq!(ones(&counters.threshold(5)), vec![5, 6, 7, 8]);
+        assert_eq!(ones(&counters.threshold(6)), vec![6, 7, 8]);
+        assert_eq!(ones(&counters.threshold(8)), vec![8]);
+        assert_eq!(ones(&counters.threshold(9)), Vec::<usize>::new());
+        assert_eq!(ones(&counters.threshold(100)), Vec::<usize>::new());
+    }
+}
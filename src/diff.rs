@@ -0,0 +1,246 @@
+use std::error::Error;
+use std::fmt::{self, Display, Formatter};
+use std::io::{Read, Write};
+use std::ops::Range;
+
+use crate::{_check_res, BVector};
+
+/// The bits that differ between two `BVector`s, as runs of positions.
+///
+/// This struct is created by the [`BVector::diff`] method.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct BVectorDiff {
+    /// Runs of bits enabled in the other vector only.
+    pub added: Vec<Range<usize>>,
+    /// Runs of bits enabled in this vector only.
+    pub removed: Vec<Range<usize>>,
+}
+
+impl BVector {
+    /// Position of the first bit with a different value in `other`, or
+    /// `None` if both vectors have the same enabled bits.
+    pub fn first_mismatch(&self, other: &BVector) -> Option<usize> {
+        let mut pi = 0;
+        let mut pfound = 0;
+
+        let res;
+        unsafe {
+            res = bitmagic_sys::BM_bvector_find_first_mismatch(
+                self.handle,
+                other.handle,
+                &mut pi,
+                &mut pfound,
+            );
+        }
+        _check_res(res);
+
+        if pfound == 1 {
+            Some(pi as usize)
+        } else {
+            None
+        }
+    }
+
+    /// The bits to enable and disable to go from this vector to `other`.
+    pub fn diff(&self, other: &BVector) -> BVectorDiff {
+        BVectorDiff {
+            added: difference(other, self).runs().collect(),
+            removed: difference(self, other).runs().collect(),
+        }
+    }
+}
+
+/// Changes turning a `BVector` into another one, to be shipped as
+/// incremental updates instead of the whole vector.
+///
+/// The delta holds the bits that differ between the two vectors, so its
+/// size depends on the amount of changes rather than on the size of the
+/// vectors. It also records a [`content_hash`] of the old vector, so it is
+/// never applied to the wrong one.
+///
+/// [`content_hash`]: BVector::content_hash
+pub struct BVectorDelta {
+    base_hash: u64,
+    changes: BVector,
+}
+
+impl BVectorDelta {
+    /// The delta from `old` to `new`.
+    pub fn between(old: &BVector, new: &BVector) -> BVectorDelta {
+        let mut changes = BVector::new();
+        changes.union_with(old);
+        changes.symmetric_difference_with(new);
+        // the capacity of the result; bits of old past it are cleared when
+        // the capacity is applied
+        changes.grow(new.raw_size());
+
+        BVectorDelta {
+            base_hash: old.content_hash(),
+            changes,
+        }
+    }
+
+    /// Return `true` if the delta leaves the enabled bits unchanged.
+    pub fn is_empty(&self) -> bool {
+        self.changes.count_all() == 0
+    }
+
+    /// Turn `old` into the vector the delta was computed for.
+    ///
+    /// Fails, leaving `old` unchanged, if it is not the vector the delta was
+    /// computed from.
+    pub fn apply(&self, old: &mut BVector) -> Result<(), BaseMismatchError> {
+        if old.content_hash() != self.base_hash {
+            return Err(BaseMismatchError);
+        }
+
+        let size = self.changes.raw_size();
+        if old.raw_size() < size {
+            old.grow(size);
+        }
+        old.symmetric_difference_with(&self.changes);
+        old.grow(size);
+
+        Ok(())
+    }
+
+    /// Serialize the delta into a writer.
+    pub fn serialize<W>(&self, mut wtr: W) -> Result<(), Box<dyn Error>>
+    where
+        W: Write,
+    {
+        wtr.write_all(&self.base_hash.to_le_bytes())?;
+        self.changes.serialize(wtr)
+    }
+
+    /// Deserialize a delta written by [`serialize`].
+    ///
+    /// [`serialize`]: BVectorDelta::serialize
+    pub fn deserialize<R>(mut rdr: R) -> Result<Self, Box<dyn Error>>
+    where
+        R: Read,
+    {
+        let mut hash = [0u8; 8];
+        rdr.read_exact(&mut hash)?;
+
+        Ok(BVectorDelta {
+            base_hash: u64::from_le_bytes(hash),
+            changes: BVector::deserialize(rdr)?,
+        })
+    }
+}
+
+/// The error returned when applying a `BVectorDelta` to a vector other than
+/// the one it was computed from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BaseMismatchError;
+
+impl Display for BaseMismatchError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "delta was computed from a different BVector")
+    }
+}
+
+impl Error for BaseMismatchError {}
+
+/// The bits of `a` not in `b`, regardless of their capacities.
+fn difference(a: &BVector, b: &BVector) -> BVector {
+    let mut diff = BVector::new();
+    diff.union_with(a);
+    diff.difference_with(b);
+    diff
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{BVectorDelta, BVectorDiff};
+    use crate::BVector;
+
+    fn bvector(bits: &[usize]) -> BVector {
+        let mut bv = BVector::new();
+        for &bit in bits {
+            bv.insert(bit);
+        }
+        bv
+    }
+
+    #[test]
+    fn first_mismatch() {
+        let a = bvector(&[1, 5, 70_000]);
+
+        assert_eq!(a.first_mismatch(&bvector(&[1, 5, 70_000])), None);
+        assert_eq!(a.first_mismatch(&bvector(&[1, 5, 70_001])), Some(70_000));
+        assert_eq!(a.first_mismatch(&bvector(&[0, 1, 5, 70_000])), Some(0));
+        assert_eq!(BVector::new().first_mismatch(&BVector::new()), None);
+    }
+
+    #[test]
+    fn diff() {
+        let old = bvector(&[1, 2, 3, 10, 11, 70_000]);
+        let new = bvector(&[2, 3, 4, 5, 11, 12, 100_000]);
+
+        assert_eq!(
+            old.diff(&new),
+            BVectorDiff {
+                added: vec![4..6, 12..13, 100_000..100_001],
+                removed: vec![1..2, 10..11, 70_000..70_001],
+            }
+        );
+        assert_eq!(old.diff(&old), BVectorDiff::default());
+    }
+
+    #[test]
+    fn delta_round_trip() {
+        let old = bvector(&[1, 2, 3, 10, 70_000]);
+        let new = bvector(&[2, 3, 4, 100_000]);
+
+        let mut buf = vec![];
+        BVectorDelta::between(&old, &new)
+            .serialize(&mut buf)
+            .unwrap();
+        let delta = BVectorDelta::deserialize(&buf[..]).unwrap();
+        assert!(!delta.is_empty());
+
+        let mut replica = old.clone();
+        delta.apply(&mut replica).unwrap();
+        assert!(replica == new);
+        assert_eq!(replica.len(), 0);
+    }
+
+    #[test]
+    fn delta_capacity() {
+        let mut old = BVector::with_capacity(100);
+        old.insert_range(90..100);
+        let mut new = BVector::with_capacity(50);
+        new.insert(7);
+
+        let mut replica = old.clone();
+        BVectorDelta::between(&old, &new)
+            .apply(&mut replica)
+            .unwrap();
+        assert_eq!(replica.len(), 50);
+        assert_eq!(replica.ones().collect::<Vec<_>>(), vec![7]);
+
+        BVectorDelta::between(&new, &old)
+            .apply(&mut replica)
+            .unwrap();
+        assert_eq!(replica.len(), 100);
+        assert_eq!(
+            replica.ones().collect::<Vec<_>>(),
+            (90..100).collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn delta_wrong_base() {
+        let old = bvector(&[1, 2]);
+        let new = bvector(&[2]);
+        let delta = BVectorDelta::between(&old, &new);
+
+        let mut other = bvector(&[1]);
+        assert!(delta.apply(&mut other).is_err());
+        assert_eq!(other.ones().collect::<Vec<_>>(), vec![1]);
+
+        assert!(BVectorDelta::between(&old, &old).is_empty());
+    }
+}
@@ -0,0 +1,175 @@
+use crate::conversions::MAX_BITS;
+use crate::fixedbitset_api::IndexRange;
+use crate::{_check_res, BVector};
+
+impl BVector {
+    /// Disable the first enabled bit and return its position, or `None` if
+    /// no bit is enabled.
+    pub fn pop_first(&mut self) -> Option<usize> {
+        self.pop_next(0)
+    }
+
+    /// Disable the first enabled bit at or after `from` and return its
+    /// position, or `None` if there is none.
+    ///
+    /// Finding and clearing the bit is a single call into BitMagic when
+    /// `from` is not 0. BitMagic reports "not found" as position 0, so from
+    /// position 0 (as in [`pop_first`]) bit 0 is read, and cleared if
+    /// enabled, by separate calls first.
+    ///
+    /// [`pop_first`]: BVector::pop_first
+    pub fn pop_next(&mut self, from: usize) -> Option<usize> {
+        if from >= MAX_BITS {
+            return None;
+        }
+
+        // extract_next reports "not found" as position 0, so bit 0 needs
+        // to be checked separately
+        if from == 0 && self.take_bit_zero() {
+            return Some(0);
+        }
+
+        let mut pnext = 0;
+
+        let res;
+        unsafe {
+            res = bitmagic_sys::BM_bvector_extract_next(self.handle, from as u32, &mut pnext);
+        }
+        _check_res(res);
+
+        if pnext == 0 {
+            None
+        } else {
+            Some(pnext as usize)
+        }
+    }
+
+    /// An iterator disabling the enabled bits and yielding their positions,
+    /// in increasing order.
+    ///
+    /// Bits are only disabled as they are yielded: if the iterator is
+    /// dropped early, the remaining bits stay enabled.
+    pub fn drain(&mut self) -> Drain<'_> {
+        self.drain_range(..)
+    }
+
+    /// Like [`drain`], limited to the bits in `range`.
+    ///
+    /// [`drain`]: BVector::drain
+    pub fn drain_range<T: IndexRange>(&mut self, range: T) -> Drain<'_> {
+        Drain {
+            next: range.start().unwrap_or(0),
+            end: range.end().unwrap_or(MAX_BITS),
+            bv: self,
+        }
+    }
+
+    /// Disable bit 0, returning whether it was enabled.
+    fn take_bit_zero(&mut self) -> bool {
+        let mut pval = 0;
+
+        let mut res;
+        unsafe {
+            res = bitmagic_sys::BM_bvector_get_bit(self.handle, 0, &mut pval);
+            _check_res(res);
+            if pval == 1 {
+                res = bitmagic_sys::BM_bvector_set_bit(self.handle, 0, 0);
+                _check_res(res);
+            }
+        }
+        pval == 1
+    }
+}
+
+/// An iterator disabling the set bits of a `BVector` and producing their
+/// indices.
+///
+/// This struct is created by the [`BVector::drain`] and
+/// [`BVector::drain_range`] methods.
+pub struct Drain<'a> {
+    bv: &'a mut BVector,
+    next: usize,
+    end: usize,
+}
+
+impl<'a> Iterator for Drain<'a> {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.next >= self.end {
+            return None;
+        }
+
+        match self.bv.pop_next(self.next) {
+            Some(bit) if bit < self.end => {
+                self.next = bit + 1;
+                Some(bit)
+            }
+            Some(bit) => {
+                // past the range: put it back
+                self.bv.insert(bit);
+                self.next = self.end;
+                None
+            }
+            None => {
+                self.next = self.end;
+                None
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::BVector;
+
+    fn sample() -> BVector {
+        let mut bv = BVector::new();
+        for &bit in &[0, 3, 4, 100, 70_000] {
+            bv.insert(bit);
+        }
+        bv
+    }
+
+    #[test]
+    fn pop() {
+        let mut bv = sample();
+
+        assert_eq!(bv.pop_first(), Some(0));
+        assert_eq!(bv.pop_first(), Some(3));
+        assert_eq!(bv.pop_next(5), Some(100));
+        assert_eq!(bv.pop_next(100), Some(70_000));
+        assert_eq!(bv.pop_next(100), None);
+        assert_eq!(bv.ones().collect::<Vec<_>>(), vec![4]);
+
+        assert_eq!(bv.pop_first(), Some(4));
+        assert_eq!(bv.pop_first(), None);
+    }
+
+    #[test]
+    fn drain() {
+        let mut bv = sample();
+
+        assert_eq!(bv.drain().collect::<Vec<_>>(), vec![0, 3, 4, 100, 70_000]);
+        assert_eq!(bv.count_all(), 0);
+    }
+
+    #[test]
+    fn drain_partially() {
+        let mut bv = sample();
+
+        assert_eq!(bv.drain().take(2).collect::<Vec<_>>(), vec![0, 3]);
+        assert_eq!(bv.ones().collect::<Vec<_>>(), vec![4, 100, 70_000]);
+    }
+
+    #[test]
+    fn drain_range() {
+        let mut bv = sample();
+
+        assert_eq!(bv.drain_range(1..100).collect::<Vec<_>>(), vec![3, 4]);
+        assert_eq!(bv.ones().collect::<Vec<_>>(), vec![0, 100, 70_000]);
+
+        assert_eq!(bv.drain_range(100..).collect::<Vec<_>>(), vec![100, 70_000]);
+        assert_eq!(bv.ones().collect::<Vec<_>>(), vec![0]);
+    }
+}
@@ -1,11 +1,13 @@
 use std::cmp::Ordering;
 use std::fmt::{Binary, Debug, Display, Error, Formatter, Write};
 use std::iter::{Chain, FromIterator};
+use std::mem;
 use std::ops::{BitAnd, BitAndAssign, BitOr, BitOrAssign, BitXor, BitXorAssign, Index};
 use std::ops::{Range, RangeFrom, RangeFull, RangeTo};
 use std::ptr;
 
-use crate::{init_lib, BVector, _check_res};
+use crate::conversions::MAX_BITS;
+use crate::{_check_res, init_lib, BVector, BVectorBuilder};
 
 impl BVector {
     /// Create a new bit-vector container with runtime compression of bits
@@ -24,7 +26,10 @@ impl BVector {
             // so we can call the _no_check() functions?
         };
         _check_res(res);
-        BVector { handle }
+        BVector {
+            handle,
+            borrowed: false,
+        }
     }
 
     /// Create a new bit-vector container with capacity (maximum number of
@@ -49,7 +54,10 @@ impl BVector {
             // so we can call the _no_check() functions?
         };
         _check_res(res);
-        BVector { handle }
+        BVector {
+            handle,
+            borrowed: false,
+        }
     }
 
     /// Grow capacity to bits, all new bits initialized to zero
@@ -589,7 +597,10 @@ impl Clone for BVector {
             // TODO: potentially call BM_bvector_init too,
             // so we can call the _no_check() functions?
         };
-        BVector { handle }
+        BVector {
+            handle,
+            borrowed: false,
+        }
     }
 }
 
@@ -614,12 +625,20 @@ impl Index<usize> for BVector {
 /// Sets the bit at index **i** to **true** for each item **i** in the input **src**.
 impl Extend<usize> for BVector {
     fn extend<I: IntoIterator<Item = usize>>(&mut self, src: I) {
-        let iter = src.into_iter();
-        for i in iter {
-            if i >= self.len() {
-                self.grow(i + 1);
+        let mut builder = BVectorBuilder::from(mem::take(self));
+
+        let mut max = None;
+        for i in src {
+            assert!(i < MAX_BITS, "bit out of bounds: {}", i);
+            builder.push(i as u32);
+            max = max.max(Some(i));
+        }
+
+        *self = builder.finish();
+        if let Some(max) = max {
+            if max >= self.len() {
+                self.grow(max + 1);
             }
-            self.put(i);
         }
     }
 }
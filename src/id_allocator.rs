@@ -0,0 +1,174 @@
+use std::error::Error;
+use std::io::{Read, Write};
+
+use crate::conversions::MAX_BITS;
+use crate::fixedbitset_api::IndexRange;
+use crate::BVector;
+
+/// Allocator of integer ids (row slots, connection ids, ...), always
+/// handing out the lowest free id.
+///
+/// The ids in use are the enabled bits of a `BVector`, so the state stays
+/// compact and can be serialized.
+pub struct IdAllocator {
+    used: BVector,
+}
+
+impl IdAllocator {
+    /// Create an allocator for all the ids a `BVector` can address.
+    pub fn new() -> IdAllocator {
+        IdAllocator::with_capacity(MAX_BITS)
+    }
+
+    /// Create an allocator for the ids in `0..capacity`.
+    pub fn with_capacity(capacity: usize) -> IdAllocator {
+        IdAllocator {
+            used: BVector::with_capacity(capacity),
+        }
+    }
+
+    /// Number of ids managed by the allocator.
+    pub fn capacity(&self) -> usize {
+        self.used.len()
+    }
+
+    /// Allocate the lowest free id, or return `None` if all the ids are in
+    /// use.
+    pub fn allocate(&mut self) -> Option<usize> {
+        let id = self.used.first_zero()?;
+        self.used.insert(id);
+        Some(id)
+    }
+
+    /// Release `id`, returning whether it was allocated.
+    pub fn release(&mut self, id: usize) -> bool {
+        let allocated = self.used.contains(id);
+        if allocated {
+            self.used.set(id, false);
+        }
+        allocated
+    }
+
+    /// Reserve all the ids in `range`, so they are not handed out.
+    ///
+    /// Returns `false`, and reserves nothing, if any of them is already
+    /// allocated.
+    ///
+    /// **Panics** if the range extends past the capacity.
+    pub fn reserve<T: IndexRange>(&mut self, range: T) -> bool {
+        let start = range.start().unwrap_or(0);
+        let end = range.end().unwrap_or_else(|| self.capacity());
+        assert!(
+            end <= self.capacity(),
+            "Range extends past the end of the vector"
+        );
+
+        if start >= end {
+            return true;
+        }
+        if self.used.count_ones(start..end) > 0 {
+            return false;
+        }
+
+        self.used.set_range(start..end, true);
+        true
+    }
+
+    /// Return `true` if `id` is allocated or reserved.
+    pub fn is_allocated(&self, id: usize) -> bool {
+        self.used.contains(id)
+    }
+
+    /// Number of allocated or reserved ids.
+    pub fn allocated(&self) -> usize {
+        self.used.count_all()
+    }
+
+    /// The ids in use, as enabled bits.
+    pub fn as_bvector(&self) -> &BVector {
+        &self.used
+    }
+
+    /// Serialize the state of the allocator, see [`BVector::serialize`].
+    pub fn serialize<W>(&self, wtr: W) -> Result<(), Box<dyn Error>>
+    where
+        W: Write,
+    {
+        self.used.serialize(wtr)
+    }
+
+    /// Restore an allocator saved with [`serialize`].
+    ///
+    /// [`serialize`]: IdAllocator::serialize
+    pub fn deserialize<R>(rdr: R) -> Result<Self, Box<dyn Error>>
+    where
+        R: Read,
+    {
+        Ok(IdAllocator {
+            used: BVector::deserialize(rdr)?,
+        })
+    }
+}
+
+impl Default for IdAllocator {
+    fn default() -> Self {
+        IdAllocator::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::IdAllocator;
+
+    #[test]
+    fn allocate_lowest() {
+        let mut ids = IdAllocator::new();
+        assert_eq!(ids.allocate(), Some(0));
+        assert_eq!(ids.allocate(), Some(1));
+        assert_eq!(ids.allocate(), Some(2));
+
+        assert!(ids.release(1));
+        assert!(!ids.release(1));
+        assert_eq!(ids.allocate(), Some(1));
+        assert_eq!(ids.allocate(), Some(3));
+        assert_eq!(ids.allocated(), 4);
+    }
+
+    #[test]
+    fn exhausted() {
+        let mut ids = IdAllocator::with_capacity(2);
+        assert_eq!(ids.allocate(), Some(0));
+        assert_eq!(ids.allocate(), Some(1));
+        assert_eq!(ids.allocate(), None);
+        assert!(!ids.release(2));
+    }
+
+    #[test]
+    fn reserve() {
+        let mut ids = IdAllocator::with_capacity(100);
+        assert!(ids.reserve(0..10));
+        assert_eq!(ids.allocate(), Some(10));
+
+        assert!(!ids.reserve(5..20));
+        assert!(!ids.is_allocated(15));
+        assert!(ids.reserve(11..21));
+        assert_eq!(ids.allocate(), Some(21));
+    }
+
+    #[test]
+    fn serde() {
+        let mut ids = IdAllocator::with_capacity(1000);
+        ids.reserve(100..200);
+        ids.allocate();
+        ids.release(0);
+        ids.allocate();
+
+        let mut wtr = vec![];
+        ids.serialize(&mut wtr).unwrap();
+
+        let mut restored = IdAllocator::deserialize(wtr.as_slice()).unwrap();
+        assert_eq!(restored.allocated(), 101);
+        assert_eq!(restored.allocate(), Some(1));
+        assert_eq!(restored.allocate(), Some(2));
+    }
+}
@@ -3,12 +3,55 @@
 
 #![deny(missing_docs)]
 
-use std::io::{Read, Write};
+use std::io::{self, Read, Write};
+use std::marker::PhantomData;
+use std::mem::ManuallyDrop;
+use std::ops::Deref;
 use std::os::raw::c_void;
 use std::ptr;
 use std::sync::Once;
 
+mod aggregate;
+mod builder;
+mod canonical;
+mod conditional;
+mod conversions;
+mod counter_vector;
+mod diff;
+mod drain;
 mod fixedbitset_api;
+mod id_allocator;
+mod merge;
+mod range;
+mod rank_select;
+mod rsc_sparse_vector;
+mod runs;
+mod scanner;
+mod shift;
+mod sparse_vector;
+mod str_sparse_vector;
+mod threshold;
+mod transform;
+mod zeros;
+
+pub mod sort;
+
+#[cfg(feature = "async")]
+mod async_io;
+
+#[cfg(feature = "arrow")]
+pub mod arrow;
+
+pub use builder::BVectorBuilder;
+pub use conversions::CapacityError;
+pub use counter_vector::CounterVector;
+pub use diff::{BVectorDelta, BVectorDiff, BaseMismatchError};
+pub use id_allocator::IdAllocator;
+pub use rank_select::RankSelect;
+pub use rsc_sparse_vector::RscSparseVector;
+pub use scanner::StrScanner;
+pub use sparse_vector::{SparseValue, SparseVector};
+pub use str_sparse_vector::StrSparseVector;
 
 /// Initialize libbm runtime before use
 fn init_lib() {
@@ -25,6 +68,73 @@ fn init_lib() {
 /// A bitvector
 pub struct BVector {
     handle: *mut c_void,
+    /// Whether the handle is owned by another container, see
+    /// [`BVectorRef`]. Borrowed handles are never modified, not even to
+    /// optimize them.
+    borrowed: bool,
+}
+
+// SAFETY: moving a `BVector` to another thread is sound because:
+// - the handle is uniquely owned: it is never shared with another `BVector`,
+//   and it is freed exactly once, by `Drop`;
+// - BitMagic vectors hold no thread-local state, and their memory is
+//   allocated with `malloc`/`free`, which may be called from any thread;
+// - the library's global state is set up once, by `init_lib`, before any
+//   handle exists.
+// `BVector` is not `Sync`: methods taking `&self`, such as the serialization
+// ones, optimize the vector in place. `BVectorRef` stays `!Send` too, since
+// it borrows a handle owned by another container.
+unsafe impl Send for BVector {}
+
+/// A `BVector` owned by another BitMagic container, such as the NULL
+/// bit-plane of a [`SparseVector`], [`RscSparseVector`] or [`StrSparseVector`].
+///
+/// It dereferences to a `&BVector`, so it works with all the read-only
+/// operations of `BVector`, and can be cloned into an owned `BVector`.
+pub struct BVectorRef<'a> {
+    bv: ManuallyDrop<BVector>,
+    _marker: PhantomData<&'a BVector>,
+}
+
+impl<'a> BVectorRef<'a> {
+    /// Wrap a bvector handle that stays valid, and is not modified, for
+    /// the lifetime `'a`.
+    pub(crate) unsafe fn from_handle(handle: *mut c_void) -> BVectorRef<'a> {
+        BVectorRef {
+            bv: ManuallyDrop::new(BVector {
+                handle,
+                borrowed: true,
+            }),
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl Deref for BVectorRef<'_> {
+    type Target = BVector;
+
+    fn deref(&self) -> &BVector {
+        &self.bv
+    }
+}
+
+impl AsRef<BVector> for BVectorRef<'_> {
+    fn as_ref(&self) -> &BVector {
+        &self.bv
+    }
+}
+
+/// Memory statistics of a BitMagic container.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MemoryStats {
+    /// Number of bit blocks.
+    pub bit_blocks: usize,
+    /// Number of GAP (run-length encoded) blocks.
+    pub gap_blocks: usize,
+    /// Worst case serialized size, in bytes.
+    pub max_serialize_mem: usize,
+    /// Memory used, in bytes.
+    pub memory_used: usize,
 }
 
 impl BVector {
@@ -33,6 +143,105 @@ impl BVector {
     where
         W: Write,
     {
+        wtr.write_all(&self.serialize_to_vec()?)?;
+
+        Ok(())
+    }
+
+    /// Serialize bit vector into a new `Vec`, holding exactly the
+    /// serialized bytes.
+    pub fn serialize_to_vec(&self) -> io::Result<Vec<u8>> {
+        let max_size = self.optimize_for_serialization();
+        self.serialize_optimized(max_size)
+    }
+
+    /// Serialize bit vector into `buf`, returning the number of bytes written.
+    ///
+    /// No allocations are made if `buf` can hold [`max_serialized_size`]
+    /// bytes, otherwise the vector is serialized into a temporary buffer
+    /// first.
+    ///
+    /// Fails with [`io::ErrorKind::WriteZero`], leaving `buf` unchanged, if
+    /// the serialized vector doesn't fit in `buf`.
+    ///
+    /// [`max_serialized_size`]: BVector::max_serialized_size
+    pub fn serialize_into(&self, buf: &mut [u8]) -> io::Result<usize> {
+        let max_size = self.optimize_for_serialization();
+        if buf.len() >= max_size {
+            return self.serialize_blob(buf);
+        }
+
+        let blob = self.serialize_optimized(max_size)?;
+        if blob.len() > buf.len() {
+            return Err(io::Error::new(
+                io::ErrorKind::WriteZero,
+                format!(
+                    "buffer too small: {} bytes needed, {} available",
+                    blob.len(),
+                    buf.len()
+                ),
+            ));
+        }
+        buf[..blob.len()].copy_from_slice(&blob);
+        Ok(blob.len())
+    }
+
+    /// Exact number of bytes written by [`serialize`], [`serialize_to_vec`]
+    /// and [`serialize_into`].
+    ///
+    /// The vector is serialized into a scratch buffer to measure it. When
+    /// the bytes are needed too, [`serialize_into`] returns the same size
+    /// with a single serialization.
+    ///
+    /// [`serialize`]: BVector::serialize
+    /// [`serialize_to_vec`]: BVector::serialize_to_vec
+    /// [`serialize_into`]: BVector::serialize_into
+    pub fn serialized_size(&self) -> io::Result<usize> {
+        Ok(self.serialize_to_vec()?.len())
+    }
+
+    /// Upper bound of the number of bytes written by [`serialize`],
+    /// [`serialize_to_vec`] and [`serialize_into`].
+    ///
+    /// The bound comes from the block statistics of the vector, without
+    /// serializing it, so buffers can be sized for [`serialize_into`]
+    /// cheaply.
+    ///
+    /// [`serialize`]: BVector::serialize
+    /// [`serialize_to_vec`]: BVector::serialize_to_vec
+    /// [`serialize_into`]: BVector::serialize_into
+    pub fn max_serialized_size(&self) -> usize {
+        self.optimize_for_serialization()
+    }
+
+    /// Memory statistics of the vector.
+    pub fn memory_stats(&self) -> MemoryStats {
+        let mut st = bitmagic_sys::BM_bvector_statistics {
+            bit_blocks: 0,
+            gap_blocks: 0,
+            max_serialize_mem: 0,
+            memory_used: 0,
+        };
+
+        let res;
+        unsafe {
+            res = bitmagic_sys::BM_bvector_calc_stat(self.handle, &mut st);
+        }
+        _check_res(res);
+
+        MemoryStats {
+            bit_blocks: st.bit_blocks,
+            gap_blocks: st.gap_blocks,
+            max_serialize_mem: st.max_serialize_mem,
+            memory_used: st.memory_used,
+        }
+    }
+
+    /// Optimize the vector for serialization, returning the worst case
+    /// serialized size.
+    ///
+    /// Vectors borrowed from another container are serialized as they are.
+    fn optimize_for_serialization(&self) -> usize {
         let mut bv_stat = bitmagic_sys::BM_bvector_statistics {
             bit_blocks: 0,
             gap_blocks: 0,
@@ -40,14 +249,34 @@ impl BVector {
             memory_used: 0,
         };
 
-        let mut res;
+        let res;
         unsafe {
-            res = bitmagic_sys::BM_bvector_optimize(self.handle, 3, &mut bv_stat);
+            res = if self.borrowed {
+                bitmagic_sys::BM_bvector_calc_stat(self.handle, &mut bv_stat)
+            } else {
+                bitmagic_sys::BM_bvector_optimize(self.handle, 3, &mut bv_stat)
+            };
         }
         _check_res(res);
 
-        let mut buf = vec![0u8; bv_stat.max_serialize_mem as usize];
+        bv_stat.max_serialize_mem
+    }
+
+    /// Serialize the (already optimized) vector into a new `Vec`, given
+    /// its worst case serialized size.
+    fn serialize_optimized(&self, max_size: usize) -> io::Result<Vec<u8>> {
+        let mut buf = vec![0u8; max_size];
+        let blob_size = self.serialize_blob(&mut buf)?;
+        buf.truncate(blob_size);
+        Ok(buf)
+    }
+
+    /// Serialize the (already optimized) vector into `buf`, which must be
+    /// able to hold the worst case serialized size.
+    fn serialize_blob(&self, buf: &mut [u8]) -> io::Result<usize> {
         let mut blob_size = 0;
+
+        let res;
         unsafe {
             res = bitmagic_sys::BM_bvector_serialize(
                 self.handle,
@@ -56,15 +285,25 @@ impl BVector {
                 &mut blob_size,
             );
         }
-        _check_res(res);
 
-        if blob_size == 0 || blob_size > bv_stat.max_serialize_mem {
-            unimplemented!("throw error")
+        if res != bitmagic_sys::BM_OK as i32 {
+            return Err(io::Error::new(
+                io::ErrorKind::Other,
+                format!("BVector serialization failed (BitMagic error {})", res),
+            ));
+        }
+        if blob_size == 0 || blob_size > buf.len() {
+            return Err(io::Error::new(
+                io::ErrorKind::Other,
+                format!(
+                    "BVector serialization returned {} bytes for a {} bytes buffer",
+                    blob_size,
+                    buf.len()
+                ),
+            ));
         }
 
-        wtr.write_all(buf.as_slice())?;
-
-        Ok(())
+        Ok(blob_size)
     }
 
     /// Deserialize bit vector
@@ -75,6 +314,12 @@ impl BVector {
         let mut buf = vec![];
         rdr.read_to_end(&mut buf)?;
 
+        Ok(BVector::deserialize_blob(&buf)?)
+    }
+
+    /// Deserialize a bit vector serialized in `buf`, failing with
+    /// [`io::ErrorKind::InvalidData`] if BitMagic rejects it.
+    pub(crate) fn deserialize_blob(buf: &[u8]) -> io::Result<BVector> {
         let bnew = BVector::with_capacity(1);
 
         let res;
@@ -85,7 +330,13 @@ impl BVector {
                 buf.len(),
             );
         }
-        _check_res(res);
+
+        if res != bitmagic_sys::BM_OK as i32 {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("invalid serialized BVector (BitMagic error {})", res),
+            ));
+        }
 
         Ok(bnew)
     }
@@ -161,6 +412,8 @@ pub(crate) fn _check_res(_res: i32) {
 
 #[cfg(test)]
 mod tests {
+    use std::io;
+
     use crate::BVector;
 
     #[test]
@@ -184,4 +437,52 @@ mod tests {
             assert!(new_bv.contains(i));
         }
     }
+
+    #[test]
+    fn serialize_exact_size() {
+        let mut bv = BVector::with_capacity(1000);
+        bv.set_range(100..200, true);
+        bv.insert(999);
+
+        let mut wtr = vec![];
+        bv.serialize(&mut wtr).unwrap();
+        assert_eq!(bv.serialize_to_vec().unwrap(), wtr);
+        assert_eq!(bv.serialized_size().unwrap(), wtr.len());
+        assert!(wtr.len() <= bv.max_serialized_size());
+
+        let mut buf = vec![0u8; bv.max_serialized_size()];
+        assert_eq!(bv.serialize_into(&mut buf).unwrap(), wtr.len());
+        assert_eq!(&buf[..wtr.len()], &wtr[..]);
+    }
+
+    #[test]
+    fn serialize_into_contiguous() {
+        let mut a = BVector::with_capacity(100);
+        a.set_range(10..20, true);
+        let mut b = BVector::with_capacity(5000);
+        b.insert(4321);
+
+        let mut page = vec![0u8; a.max_serialized_size() + b.max_serialized_size()];
+        let a_size = a.serialize_into(&mut page).unwrap();
+        let b_size = b.serialize_into(&mut page[a_size..]).unwrap();
+        page.truncate(a_size + b_size);
+
+        assert_eq!(BVector::deserialize(&page[..a_size]).unwrap(), a);
+        assert_eq!(BVector::deserialize(&page[a_size..]).unwrap(), b);
+    }
+
+    #[test]
+    fn serialize_into_too_small() {
+        let mut bv = BVector::with_capacity(100);
+        bv.set_range(10..20, true);
+        let size = bv.serialize_to_vec().unwrap().len();
+
+        let mut buf = vec![0u8; size - 1];
+        let err = bv.serialize_into(&mut buf).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::WriteZero);
+        assert!(buf.iter().all(|&byte| byte == 0));
+
+        let mut buf = vec![0u8; size];
+        assert_eq!(bv.serialize_into(&mut buf).unwrap(), size);
+    }
 }
@@ -0,0 +1,119 @@
+use std::mem;
+
+use crate::{_check_res, BVector};
+
+impl BVector {
+    /// Union `other` into this vector, consuming it.
+    ///
+    /// Unlike [`union_with`], the blocks of `other` that have no
+    /// counterpart here are moved rather than copied, so merging disjoint
+    /// vectors does not allocate.
+    ///
+    /// [`union_with`]: BVector::union_with
+    pub fn merge(&mut self, mut other: BVector) {
+        self.merge_from(&mut other);
+    }
+
+    /// Union `other` into this vector, leaving in `other` only the blocks
+    /// that could not be moved.
+    fn merge_from(&mut self, other: &mut BVector) {
+        let res;
+        unsafe {
+            res = bitmagic_sys::BM_bvector_merge(self.handle, other.handle);
+        }
+        _check_res(res);
+    }
+
+    /// Exchange the contents of two vectors, without copying their blocks.
+    pub fn swap(&mut self, other: &mut BVector) {
+        let res;
+        unsafe {
+            res = bitmagic_sys::BM_bvector_swap(self.handle, other.handle);
+        }
+        _check_res(res);
+    }
+
+    /// Take the contents of the vector, leaving an empty unbounded vector
+    /// in its place.
+    pub fn take(&mut self) -> BVector {
+        mem::take(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::BVector;
+
+    fn blocks(from: usize, count: usize) -> BVector {
+        // one dense bit block per 65536 bits
+        let mut bv = BVector::new();
+        for block in from..from + count {
+            for bit in (0..65_536).step_by(3) {
+                bv.insert(block * 65_536 + bit);
+            }
+        }
+        bv
+    }
+
+    #[test]
+    fn merge() {
+        let mut dst = blocks(0, 4);
+        let src = blocks(4, 4);
+        let expected = dst.count_all() + src.count_all();
+
+        dst.merge(src);
+
+        assert_eq!(dst.count_all(), expected);
+        assert_eq!(dst.memory_stats().bit_blocks, 8);
+    }
+
+    #[test]
+    fn merge_moves_blocks() {
+        let mut dst = blocks(0, 4);
+        let mut src = blocks(4, 4);
+        let expected = dst.count_all() + src.count_all();
+
+        dst.merge_from(&mut src);
+
+        // a copying union would leave the blocks of src in place
+        assert_eq!(dst.count_all(), expected);
+        assert_eq!(src.memory_stats().bit_blocks, 0);
+        assert_eq!(src.count_all(), 0);
+    }
+
+    #[test]
+    fn merge_overlapping() {
+        let mut dst = BVector::new();
+        dst.insert(1);
+        dst.insert(5);
+        let mut src = BVector::new();
+        src.insert(5);
+        src.insert(70_000);
+
+        dst.merge(src);
+        assert_eq!(dst.ones().collect::<Vec<_>>(), vec![1, 5, 70_000]);
+    }
+
+    #[test]
+    fn swap() {
+        let mut a = blocks(0, 2);
+        let mut b = BVector::with_capacity(10);
+        b.insert(3);
+        let a_mem = a.memory_stats().memory_used;
+
+        a.swap(&mut b);
+        assert_eq!(a.ones().collect::<Vec<_>>(), vec![3]);
+        assert_eq!(a.len(), 10);
+        assert_eq!(b.count_all(), 2 * 21_846);
+        assert_eq!(b.memory_stats().memory_used, a_mem);
+    }
+
+    #[test]
+    fn take() {
+        let mut a = blocks(0, 1);
+        let taken = a.take();
+
+        assert_eq!(taken.count_all(), 21_846);
+        assert_eq!(a.count_all(), 0);
+    }
+}
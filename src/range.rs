@@ -0,0 +1,144 @@
+use crate::fixedbitset_api::IndexRange;
+use crate::{_check_res, BVector};
+
+impl BVector {
+    /// A new vector with the same capacity, holding only the bits of this
+    /// one in `range`.
+    ///
+    /// Only the blocks overlapping `range` are copied.
+    ///
+    /// **Panics** if the range extends past the end of the vector.
+    pub fn copy_range<T: IndexRange>(&self, range: T) -> BVector {
+        let mut copy = BVector::new();
+
+        match self.closed_range(range) {
+            Some((left, right)) => {
+                let res;
+                unsafe {
+                    res =
+                        bitmagic_sys::BM_bvector_copy_range(copy.handle, self.handle, left, right);
+                }
+                _check_res(res);
+            }
+            None if !self.is_empty() => copy.grow(self.len()),
+            None => {}
+        }
+        copy
+    }
+
+    /// Disable every bit outside of `range`.
+    ///
+    /// The blocks outside of `range` are freed rather than cleared bit by
+    /// bit.
+    ///
+    /// **Panics** if the range extends past the end of the vector.
+    pub fn keep_range<T: IndexRange>(&mut self, range: T) {
+        match self.closed_range(range) {
+            Some((left, right)) => {
+                let res;
+                unsafe {
+                    res = bitmagic_sys::BM_bvector_keep_range(self.handle, left, right);
+                }
+                _check_res(res);
+            }
+            None => self.clear(),
+        }
+    }
+
+    /// Disable every bit in `range`.
+    ///
+    /// Unlike `set_range(range, false)`, this works on vectors without a
+    /// fixed capacity, and open ranges stop at the last addressable bit.
+    ///
+    /// **Panics** if the range extends past the end of the vector.
+    pub fn clear_range<T: IndexRange>(&mut self, range: T) {
+        if let Some((left, right)) = self.closed_range(range) {
+            let res;
+            unsafe {
+                res = bitmagic_sys::BM_bvector_set_range(self.handle, left, right, 0);
+            }
+            _check_res(res);
+        }
+    }
+
+    /// First and last bits of `range`, or `None` if it is empty.
+    fn closed_range<T: IndexRange>(&self, range: T) -> Option<(u32, u32)> {
+        let limit = self.bit_limit();
+        let start = range.start().unwrap_or(0);
+        let end = range.end().unwrap_or(limit);
+        assert!(end <= limit, "Range extends past the end of the vector");
+
+        if start >= end {
+            None
+        } else {
+            Some((start as u32, (end - 1) as u32))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::BVector;
+
+    fn sample() -> BVector {
+        let mut bv = BVector::new();
+        for &bit in &[0, 3, 4, 100, 70_000, 200_000] {
+            bv.insert(bit);
+        }
+        bv
+    }
+
+    fn ones(bv: &BVector) -> Vec<usize> {
+        bv.ones().collect()
+    }
+
+    #[test]
+    fn copy_range() {
+        let bv = sample();
+
+        assert_eq!(ones(&bv.copy_range(3..70_001)), vec![3, 4, 100, 70_000]);
+        assert_eq!(ones(&bv.copy_range(101..)), vec![70_000, 200_000]);
+        assert_eq!(ones(&bv.copy_range(5..5)), Vec::<usize>::new());
+        assert_eq!(ones(&bv), vec![0, 3, 4, 100, 70_000, 200_000]);
+    }
+
+    #[test]
+    fn copy_range_keeps_capacity() {
+        let mut bv = BVector::with_capacity(50);
+        bv.insert_range(10..20);
+
+        let copy = bv.copy_range(15..30);
+        assert_eq!(copy.len(), 50);
+        assert_eq!(ones(&copy), (15..20).collect::<Vec<_>>());
+
+        assert_eq!(bv.copy_range(30..30).len(), 50);
+    }
+
+    #[test]
+    fn keep_range() {
+        let mut bv = sample();
+
+        bv.keep_range(4..100_000);
+        assert_eq!(ones(&bv), vec![4, 100, 70_000]);
+
+        bv.keep_range(..0);
+        assert_eq!(ones(&bv), Vec::<usize>::new());
+    }
+
+    #[test]
+    fn clear_range() {
+        let mut bv = sample();
+
+        bv.clear_range(1..101);
+        assert_eq!(ones(&bv), vec![0, 70_000, 200_000]);
+
+        bv.clear_range(100_000..);
+        assert_eq!(ones(&bv), vec![0, 70_000]);
+    }
+
+    #[should_panic]
+    #[test]
+    fn range_out_of_bounds() {
+        BVector::with_capacity(10).keep_range(..11);
+    }
+}
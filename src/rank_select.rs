@@ -0,0 +1,293 @@
+use std::os::raw::c_void;
+use std::ptr;
+
+use crate::conversions::MAX_BITS;
+use crate::fixedbitset_api::IndexRange;
+use crate::{_check_res, BVector};
+
+/// A BitMagic rank-select index (`bvector<>::rs_index_type`).
+struct RsIndex {
+    handle: *mut c_void,
+}
+
+impl RsIndex {
+    fn build(bv: &BVector) -> RsIndex {
+        let mut handle = ptr::null_mut();
+
+        let mut res;
+        unsafe {
+            res = bitmagic_sys::BM_rs_index_construct(&mut handle);
+            _check_res(res);
+            res = bitmagic_sys::BM_bvector_build_rs_index(bv.handle, handle);
+            _check_res(res);
+        }
+
+        RsIndex { handle }
+    }
+}
+
+// Like `BVector`, the index is uniquely owned and not tied to a thread.
+unsafe impl Send for RsIndex {}
+
+impl Drop for RsIndex {
+    fn drop(&mut self) {
+        unsafe {
+            bitmagic_sys::BM_rs_index_free(self.handle);
+        }
+    }
+}
+
+/// A frozen `BVector` with a rank-select index, answering `rank` and
+/// `select` queries in constant time.
+///
+/// The index is built over the vector when the `RankSelect` is created.
+/// The vector can only be modified through [`update`], which rebuilds the
+/// index, so queries never see a stale index.
+///
+/// [`update`]: RankSelect::update
+pub struct RankSelect {
+    bv: BVector,
+    rs_index: RsIndex,
+    ones: usize,
+}
+
+impl RankSelect {
+    /// Freeze `bv` and build its rank-select index.
+    pub fn new(bv: BVector) -> RankSelect {
+        let rs_index = RsIndex::build(&bv);
+        let ones = bv.count_all();
+        RankSelect { bv, rs_index, ones }
+    }
+
+    /// The indexed `BVector`.
+    pub fn as_bvector(&self) -> &BVector {
+        &self.bv
+    }
+
+    /// Return the indexed `BVector`, dropping the index.
+    pub fn into_inner(self) -> BVector {
+        self.bv
+    }
+
+    /// Modify the indexed `BVector`, rebuilding the index afterwards.
+    pub fn update<F>(&mut self, f: F)
+    where
+        F: FnOnce(&mut BVector),
+    {
+        f(&mut self.bv);
+        self.rs_index = RsIndex::build(&self.bv);
+        self.ones = self.bv.count_all();
+    }
+
+    /// Number of enabled bits before `bit`, that is, in `..bit`.
+    pub fn rank1(&self, bit: usize) -> usize {
+        if bit == 0 {
+            return 0;
+        }
+
+        let mut pcount = 0;
+
+        let res;
+        unsafe {
+            res = bitmagic_sys::BM_bvector_count_to_rs(
+                self.bv.handle,
+                self.rs_index.handle,
+                (bit.min(MAX_BITS) - 1) as u32,
+                &mut pcount,
+            );
+        }
+        _check_res(res);
+
+        pcount as usize
+    }
+
+    /// Number of disabled bits before `bit`, that is, in `..bit`.
+    pub fn rank0(&self, bit: usize) -> usize {
+        bit - self.rank1(bit)
+    }
+
+    /// Position of the `n`-th enabled bit (starting from 0), or `None` if
+    /// there are not enough enabled bits.
+    pub fn select1(&self, n: usize) -> Option<usize> {
+        if n >= self.ones {
+            return None;
+        }
+
+        let mut pos = 0;
+        let mut found = 0;
+
+        let res;
+        unsafe {
+            res = bitmagic_sys::BM_bvector_select_rs(
+                self.bv.handle,
+                self.rs_index.handle,
+                (n + 1) as u32,
+                &mut pos,
+                &mut found,
+            );
+        }
+        _check_res(res);
+
+        if found == 1 {
+            Some(pos as usize)
+        } else {
+            None
+        }
+    }
+
+    /// Position of the `n`-th disabled bit (starting from 0), or `None` if
+    /// there are not enough disabled bits within the capacity.
+    ///
+    /// BitMagic has no select index for disabled bits, so this is a binary
+    /// search over [`rank0`], in `O(log(len))`.
+    ///
+    /// [`rank0`]: RankSelect::rank0
+    pub fn select0(&self, n: usize) -> Option<usize> {
+        let size = self.size();
+        if n >= size - self.ones {
+            return None;
+        }
+
+        // smallest position with n + 1 disabled bits in ..=pos
+        let (mut lo, mut hi) = (0, size - 1);
+        while lo < hi {
+            let mid = lo + (hi - lo) / 2;
+            if self.rank0(mid + 1) > n {
+                hi = mid;
+            } else {
+                lo = mid + 1;
+            }
+        }
+        Some(lo)
+    }
+
+    /// Count the number of enabled bits in the given bit range.
+    ///
+    /// Use `..` to count the whole content of the bitset.
+    ///
+    /// **Panics** if the range extends past the end of the bitset.
+    pub fn count_range<T: IndexRange>(&self, range: T) -> usize {
+        let size = self.size();
+        let start = range.start().unwrap_or(0);
+        let end = range.end().unwrap_or(size);
+        assert!(end <= size, "Range extends past the end of the vector");
+        assert!(start <= end);
+
+        if start == end {
+            return 0;
+        }
+
+        let mut pcount = 0;
+
+        let res;
+        unsafe {
+            res = bitmagic_sys::BM_bvector_count_range_rs(
+                self.bv.handle,
+                self.rs_index.handle,
+                start as u32,
+                (end - 1) as u32,
+                &mut pcount,
+            );
+        }
+        _check_res(res);
+
+        pcount as usize
+    }
+
+    /// Number of bits addressable in the vector.
+    fn size(&self) -> usize {
+        match self.bv.len() {
+            0 => MAX_BITS,
+            len => len,
+        }
+    }
+}
+
+impl AsRef<BVector> for RankSelect {
+    fn as_ref(&self) -> &BVector {
+        &self.bv
+    }
+}
+
+impl From<BVector> for RankSelect {
+    fn from(bv: BVector) -> RankSelect {
+        RankSelect::new(bv)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::RankSelect;
+    use crate::BVector;
+
+    fn sample() -> BVector {
+        let mut bv = BVector::with_capacity(1000);
+        for i in (0..1000).filter(|i| i % 7 == 0 || (300..350).contains(i)) {
+            bv.insert(i);
+        }
+        bv
+    }
+
+    #[test]
+    fn rank() {
+        let bv = sample();
+        let rs = RankSelect::new(bv.clone());
+
+        assert_eq!(rs.rank1(0), 0);
+        for i in 1..=1000 {
+            assert_eq!(rs.rank1(i), bv.count_ones(..i));
+            assert_eq!(rs.rank0(i), i - bv.count_ones(..i));
+        }
+    }
+
+    #[test]
+    fn select() {
+        let bv = sample();
+        let rs = RankSelect::new(bv.clone());
+
+        let ones: Vec<usize> = bv.ones().collect();
+        for (n, pos) in ones.iter().enumerate() {
+            assert_eq!(rs.select1(n), Some(*pos));
+        }
+        assert_eq!(rs.select1(ones.len()), None);
+
+        let zeros: Vec<usize> = (0..1000).filter(|i| !bv.contains(*i)).collect();
+        for (n, pos) in zeros.iter().enumerate() {
+            assert_eq!(rs.select0(n), Some(*pos));
+        }
+        assert_eq!(rs.select0(zeros.len()), None);
+    }
+
+    #[test]
+    fn count_range() {
+        let bv = sample();
+        let rs = RankSelect::new(bv.clone());
+
+        assert_eq!(rs.count_range(..), bv.count_ones(..));
+        assert_eq!(rs.count_range(290..360), bv.count_ones(290..360));
+        assert_eq!(rs.count_range(7..7), 0);
+        assert_eq!(rs.count_range(990..), bv.count_ones(990..));
+    }
+
+    #[should_panic]
+    #[test]
+    fn count_range_oob() {
+        let rs = RankSelect::new(sample());
+        rs.count_range(900..1001);
+    }
+
+    #[test]
+    fn update_rebuilds_index() {
+        let mut rs = RankSelect::new(sample());
+        let before = rs.rank1(1000);
+
+        rs.update(|bv| bv.set_range(300..350, false));
+
+        assert_eq!(rs.rank1(1000), rs.as_bvector().count_ones(..));
+        assert!(rs.rank1(1000) < before);
+        assert_eq!(rs.select1(0), Some(0));
+
+        let bv = rs.into_inner();
+        assert!(!bv.contains(310));
+    }
+}
@@ -0,0 +1,352 @@
+use std::error::Error;
+use std::io::{Read, Write};
+use std::marker::PhantomData;
+use std::os::raw::c_void;
+use std::ptr;
+
+use crate::conversions::MAX_BITS;
+use crate::fixedbitset_api::IndexRange;
+use crate::sparse_vector::sealed::RawValue;
+use crate::sparse_vector::{with_nulls, write_ext_buffer};
+use crate::{_check_res, init_lib, BVectorRef, SparseValue, SparseVector};
+
+/// A succinct vector of integers for mostly NULL columns
+/// (BitMagic `rsc_sparse_vector`).
+///
+/// Only the values that are not NULL are stored, together with a bitmap of
+/// the rows that have a value. The bitmap has a rank-select index, so
+/// random access stays fast.
+///
+/// The vector is read-only once built, either from `(row, value)` pairs
+/// with [`from_pairs`] or from a [`SparseVector`].
+///
+/// [`from_pairs`]: RscSparseVector::from_pairs
+pub struct RscSparseVector<T: SparseValue> {
+    handle: *mut c_void,
+    _marker: PhantomData<T>,
+}
+
+// Like `BVector`, the handle is uniquely owned and not tied to a thread.
+unsafe impl<T: SparseValue> Send for RscSparseVector<T> {}
+
+impl<T: SparseValue> RscSparseVector<T> {
+    fn empty() -> RscSparseVector<T> {
+        init_lib();
+        let mut handle = ptr::null_mut();
+
+        let res;
+        unsafe {
+            res = T::Raw::rsc_construct(&mut handle);
+        }
+        _check_res(res);
+
+        RscSparseVector {
+            handle,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Build a vector out of `(row, value)` pairs, in increasing row order.
+    /// Rows without a value are NULL, and the vector ends at the last row.
+    ///
+    /// **Panics** if the rows are not strictly increasing, or are out of
+    /// the addressable range.
+    pub fn from_pairs<I>(pairs: I) -> RscSparseVector<T>
+    where
+        I: IntoIterator<Item = (usize, T)>,
+    {
+        let rsc = RscSparseVector::empty();
+
+        let mut next_row = 0;
+        for (row, value) in pairs {
+            assert!(
+                row >= next_row,
+                "rows must be strictly increasing, got {} after {}",
+                row,
+                next_row - 1
+            );
+            assert!(row < MAX_BITS, "row out of bounds: {}", row);
+
+            let res;
+            unsafe {
+                res = T::Raw::rsc_push_back(rsc.handle, row as u32, value.to_raw());
+            }
+            _check_res(res);
+
+            next_row = row + 1;
+        }
+
+        rsc.sync();
+        rsc
+    }
+
+    /// Decompress into a `SparseVector`.
+    pub fn to_sparse_vector(&self) -> SparseVector<T> {
+        let sv = SparseVector::new();
+
+        let res;
+        unsafe {
+            res = T::Raw::rsc_load_to(self.handle, sv.handle);
+        }
+        _check_res(res);
+
+        sv
+    }
+
+    /// Number of rows, NULL or not.
+    pub fn len(&self) -> usize {
+        let mut psize = 0;
+
+        let res;
+        unsafe {
+            res = T::Raw::rsc_size(self.handle, &mut psize);
+        }
+        _check_res(res);
+
+        psize as usize
+    }
+
+    /// Return `true` if the vector has no rows.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Value of `row`, or `None` if it is NULL or past the end of the
+    /// vector.
+    pub fn get(&self, row: usize) -> Option<T> {
+        if row >= self.len() {
+            return None;
+        }
+
+        let mut value = T::Raw::default();
+        let mut null = 0;
+
+        let res;
+        unsafe {
+            res = T::Raw::rsc_get(self.handle, row as u32, &mut value, &mut null);
+        }
+        _check_res(res);
+
+        if null == 0 {
+            Some(T::from_raw(value))
+        } else {
+            None
+        }
+    }
+
+    /// Decode the rows in the given range, with `None` for NULLs.
+    ///
+    /// Use `..` to decode the whole vector.
+    ///
+    /// **Panics** if the range extends past the end of the vector.
+    pub fn decode_range<R: IndexRange>(&self, range: R) -> Vec<Option<T>> {
+        let len = self.len();
+        let start = range.start().unwrap_or(0);
+        let end = range.end().unwrap_or(len);
+        assert!(end <= len, "Range extends past the end of the vector");
+        assert!(start <= end);
+
+        if start == end {
+            return vec![];
+        }
+
+        let mut raw = vec![T::Raw::default(); end - start];
+        let mut decoded = 0;
+
+        let res;
+        unsafe {
+            res = T::Raw::rsc_decode(
+                self.handle,
+                raw.as_mut_ptr(),
+                start as u32,
+                raw.len(),
+                &mut decoded,
+            );
+        }
+        _check_res(res);
+
+        with_nulls(&self.not_null(), start, &raw)
+    }
+
+    /// Optimize the memory layout of the vector.
+    pub fn optimize(&mut self) {
+        let res;
+        unsafe {
+            res = T::Raw::rsc_optimize(self.handle);
+        }
+        _check_res(res);
+    }
+
+    /// The not-NULL bitmap: its enabled bits are the rows with a value.
+    pub fn not_null(&self) -> BVectorRef<'_> {
+        let mut bv = ptr::null_mut();
+
+        let res;
+        unsafe {
+            res = T::Raw::rsc_get_null_bvector(self.handle, &mut bv);
+        }
+        _check_res(res);
+
+        unsafe { BVectorRef::from_handle(bv) }
+    }
+
+    /// Serialize succinct sparse vector
+    pub fn serialize<W>(&self, wtr: W) -> Result<(), Box<dyn Error>>
+    where
+        W: Write,
+    {
+        let mut buf = ptr::null_mut();
+        let mut size = 0;
+
+        let res;
+        unsafe {
+            res = T::Raw::rsc_serialize(self.handle, &mut buf, &mut size);
+        }
+        _check_res(res);
+
+        write_ext_buffer(buf, size, wtr)
+    }
+
+    /// Deserialize succinct sparse vector
+    pub fn deserialize<R>(mut rdr: R) -> Result<Self, Box<dyn Error>>
+    where
+        R: Read,
+    {
+        let mut buf = vec![];
+        rdr.read_to_end(&mut buf)?;
+
+        let rnew = RscSparseVector::<T>::empty();
+
+        let res;
+        unsafe {
+            res = T::Raw::rsc_deserialize(rnew.handle, buf.as_ptr(), buf.len());
+        }
+
+        if res != bitmagic_sys::BM_OK as i32 {
+            return Err("invalid serialized RscSparseVector".into());
+        }
+
+        Ok(rnew)
+    }
+
+    /// Rebuild the rank index of the not-NULL bitmap, after it changed.
+    fn sync(&self) {
+        let res;
+        unsafe {
+            res = T::Raw::rsc_sync(self.handle);
+        }
+        _check_res(res);
+    }
+}
+
+impl<T: SparseValue> From<&SparseVector<T>> for RscSparseVector<T> {
+    fn from(sv: &SparseVector<T>) -> Self {
+        let rsc = RscSparseVector::empty();
+
+        let res;
+        unsafe {
+            res = T::Raw::rsc_load_from(rsc.handle, sv.handle);
+        }
+        _check_res(res);
+
+        rsc
+    }
+}
+
+impl<T: SparseValue> Clone for RscSparseVector<T> {
+    fn clone(&self) -> Self {
+        let mut handle = ptr::null_mut();
+
+        let res;
+        unsafe {
+            res = T::Raw::rsc_construct_copy(&mut handle, self.handle);
+        }
+        _check_res(res);
+
+        RscSparseVector {
+            handle,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<T: SparseValue> Drop for RscSparseVector<T> {
+    fn drop(&mut self) {
+        unsafe {
+            T::Raw::rsc_free(self.handle);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::RscSparseVector;
+    use crate::{BVector, SparseVector};
+
+    fn sample() -> RscSparseVector<i32> {
+        RscSparseVector::from_pairs(vec![(3, -3), (1000, 10), (1001, 0), (50_000, 7)])
+    }
+
+    #[test]
+    fn from_pairs() {
+        let rsc = sample();
+
+        assert_eq!(rsc.len(), 50_001);
+        assert_eq!(rsc.get(0), None);
+        assert_eq!(rsc.get(3), Some(-3));
+        assert_eq!(rsc.get(1001), Some(0));
+        assert_eq!(rsc.get(50_000), Some(7));
+        assert_eq!(rsc.get(50_001), None);
+
+        assert_eq!(
+            rsc.decode_range(999..1003),
+            vec![None, Some(10), Some(0), None]
+        );
+    }
+
+    #[should_panic]
+    #[test]
+    fn from_unsorted_pairs() {
+        RscSparseVector::from_pairs(vec![(10, 1u32), (10, 2)]);
+    }
+
+    #[test]
+    fn sparse_vector_roundtrip() {
+        let mut sv = SparseVector::<u64>::new();
+        sv.set(10, 100);
+        sv.set(20, 200);
+        sv.set_null(29);
+
+        let rsc = RscSparseVector::from(&sv);
+        assert_eq!(rsc.len(), 30);
+        assert_eq!(rsc.decode_range(..), sv.decode_range(..));
+
+        assert_eq!(rsc.to_sparse_vector().decode_range(..), sv.decode_range(..));
+    }
+
+    #[test]
+    fn not_null() {
+        let rsc = sample();
+
+        let mut rows = BVector::with_capacity(2000);
+        rows.set_range(.., true);
+        rows.intersect_with(&rsc.not_null());
+        assert_eq!(rows.ones().collect::<Vec<_>>(), vec![3, 1000, 1001]);
+    }
+
+    #[test]
+    fn serde() {
+        let mut rsc = sample();
+        rsc.optimize();
+
+        let mut wtr = vec![];
+        rsc.serialize(&mut wtr).unwrap();
+
+        let new_rsc = RscSparseVector::<i32>::deserialize(wtr.as_slice()).unwrap();
+        assert_eq!(new_rsc.len(), rsc.len());
+        assert_eq!(new_rsc.get(1000), Some(10));
+        assert_eq!(new_rsc.decode_range(..), rsc.decode_range(..));
+        assert_eq!(new_rsc.get(50_000), Some(7));
+        assert_eq!(new_rsc.get(49_999), None);
+    }
+}
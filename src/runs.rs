@@ -0,0 +1,222 @@
+use std::ops::Range;
+
+use crate::conversions::MAX_BITS;
+use crate::{_check_res, BVector};
+
+/// Number of runs fetched from BitMagic at a time.
+const CHUNK: usize = 1024;
+
+impl BVector {
+    /// Create a vector with the bits in `ranges` enabled.
+    ///
+    /// Each range is set with a single call into BitMagic, which fills
+    /// whole blocks at once.
+    ///
+    /// **Panics** if a range extends past the addressable bits.
+    pub fn from_ranges<I>(ranges: I) -> BVector
+    where
+        I: IntoIterator<Item = Range<usize>>,
+    {
+        let bv = BVector::new();
+        for range in ranges {
+            assert!(
+                range.end <= MAX_BITS,
+                "Range extends past the end of the vector"
+            );
+            if range.start >= range.end {
+                continue;
+            }
+
+            let res;
+            unsafe {
+                res = bitmagic_sys::BM_bvector_set_range(
+                    bv.handle,
+                    range.start as u32,
+                    (range.end - 1) as u32,
+                    1,
+                );
+            }
+            _check_res(res);
+        }
+        bv
+    }
+
+    /// An iterator over the runs of enabled bits, in increasing order.
+    ///
+    /// Runs are read from BitMagic's interval enumerator, in batches, so
+    /// long runs cost the same as single bits.
+    pub fn runs(&self) -> Runs<'_> {
+        Runs {
+            bv: self,
+            buf: vec![],
+            pos: 0,
+            from: Some(0),
+        }
+    }
+
+    /// An iterator over the runs of disabled bits, in increasing order.
+    ///
+    /// The last run ends at the capacity of the vector, or at the last
+    /// addressable bit for vectors without a fixed capacity.
+    pub fn runs_of_zeros(&self) -> ZeroRuns<'_> {
+        ZeroRuns {
+            runs: self.runs(),
+            pos: 0,
+            end: self.bit_limit(),
+        }
+    }
+}
+
+/// An iterator producing the runs of enabled bits of a `BVector`.
+///
+/// This struct is created by the [`BVector::runs`] method.
+pub struct Runs<'a> {
+    bv: &'a BVector,
+    /// First and last positions of the runs fetched so far.
+    buf: Vec<u32>,
+    pos: usize,
+    /// Where to fetch the next batch from, `None` once all the runs are
+    /// fetched.
+    from: Option<u32>,
+}
+
+impl<'a> Runs<'a> {
+    fn fetch(&mut self, from: u32) {
+        self.buf.resize(2 * CHUNK, 0);
+        let mut count = 0;
+
+        let res;
+        unsafe {
+            res = bitmagic_sys::BM_bvector_export_runs(
+                self.bv.handle,
+                from,
+                self.buf.as_mut_ptr(),
+                CHUNK,
+                &mut count,
+            );
+        }
+        _check_res(res);
+
+        self.buf.truncate(2 * count);
+        self.pos = 0;
+
+        let last = self.buf.last().map(|&last| last as usize);
+        self.from = match last {
+            Some(last) if count == CHUNK && last < MAX_BITS - 1 => Some(last as u32 + 1),
+            _ => None,
+        };
+    }
+}
+
+impl<'a> Iterator for Runs<'a> {
+    type Item = Range<usize>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.pos == self.buf.len() {
+            let from = self.from?;
+            self.fetch(from);
+            if self.buf.is_empty() {
+                return None;
+            }
+        }
+
+        let run = self.buf[self.pos] as usize..self.buf[self.pos + 1] as usize + 1;
+        self.pos += 2;
+        Some(run)
+    }
+}
+
+/// An iterator producing the runs of disabled bits of a `BVector`.
+///
+/// This struct is created by the [`BVector::runs_of_zeros`] method.
+pub struct ZeroRuns<'a> {
+    runs: Runs<'a>,
+    pos: usize,
+    end: usize,
+}
+
+impl<'a> Iterator for ZeroRuns<'a> {
+    type Item = Range<usize>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        for run in &mut self.runs {
+            let gap = self.pos..run.start;
+            self.pos = run.end;
+            if gap.start < gap.end {
+                return Some(gap);
+            }
+        }
+
+        if self.pos < self.end {
+            let gap = self.pos..self.end;
+            self.pos = self.end;
+            Some(gap)
+        } else {
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::iter;
+
+    use crate::conversions::MAX_BITS;
+    use crate::BVector;
+
+    #[test]
+    fn runs() {
+        let bv = BVector::from_ranges(vec![0..3, 10..11, 65_530..200_000, 5..5]);
+
+        assert_eq!(
+            bv.runs().collect::<Vec<_>>(),
+            vec![0..3, 10..11, 65_530..200_000]
+        );
+        assert_eq!(bv.count_all(), 3 + 1 + 200_000 - 65_530);
+        assert_eq!(BVector::new().runs().count(), 0);
+    }
+
+    #[test]
+    fn many_runs() {
+        // more runs than fetched in one batch
+        let ranges: Vec<_> = (0..3000).map(|i| 3 * i..3 * i + 2).collect();
+        let bv = BVector::from_ranges(ranges.clone());
+
+        assert_eq!(bv.runs().collect::<Vec<_>>(), ranges);
+    }
+
+    #[test]
+    fn overlapping_ranges() {
+        let bv = BVector::from_ranges(vec![5..10, 8..12, 12..13]);
+        assert_eq!(bv.runs().collect::<Vec<_>>(), vec![5..13]);
+    }
+
+    #[test]
+    fn runs_of_zeros() {
+        let mut bv = BVector::with_capacity(100);
+        bv.insert_range(0..3);
+        bv.insert_range(10..20);
+        bv.insert(50);
+
+        assert_eq!(
+            bv.runs_of_zeros().collect::<Vec<_>>(),
+            vec![3..10, 20..50, 51..100]
+        );
+
+        bv.insert_range(51..);
+        assert_eq!(bv.runs_of_zeros().collect::<Vec<_>>(), vec![3..10, 20..50]);
+
+        assert_eq!(
+            BVector::from_ranges(iter::once(1..2))
+                .runs_of_zeros()
+                .collect::<Vec<_>>(),
+            vec![0..1, 2..MAX_BITS]
+        );
+    }
+
+    #[should_panic]
+    #[test]
+    fn range_out_of_bounds() {
+        BVector::from_ranges(iter::once(0..MAX_BITS + 1));
+    }
+}
@@ -0,0 +1,404 @@
+use std::ops::{Bound, RangeBounds};
+use std::os::raw::c_void;
+use std::ptr;
+
+use crate::sparse_vector::sealed::RawValue;
+use crate::str_sparse_vector::to_c_str;
+use crate::{_check_res, BVector, SparseValue, SparseVector, StrSparseVector};
+
+impl<T: SparseValue> SparseVector<T> {
+    /// Find the elements equal to `value`.
+    ///
+    /// The search runs over the bit-planes of the vector, without decoding
+    /// it. The result has the positions of the matching elements enabled,
+    /// and the length of the vector as capacity, so it can be combined with
+    /// other results.
+    pub fn find_eq(&self, value: T) -> BVector {
+        let found = BVector::with_capacity(self.len());
+
+        let res;
+        unsafe {
+            res = T::Raw::find_eq(self.handle, value.to_raw(), found.handle);
+        }
+        _check_res(res);
+
+        found
+    }
+
+    /// Find the elements with a value in `range`, which can be any kind of
+    /// range (`lo..hi`, `lo..=hi`, `..hi`, ...).
+    pub fn find_range<R: RangeBounds<T>>(&self, range: R) -> BVector {
+        let found = BVector::with_capacity(self.len());
+
+        // raw values are unsigned, so the default is the smallest one
+        let from = match range.start_bound() {
+            Bound::Included(v) => Some(v.to_raw()),
+            Bound::Excluded(v) => v.to_raw().checked_inc(),
+            Bound::Unbounded => Some(T::Raw::default()),
+        };
+        let to = match range.end_bound() {
+            Bound::Included(v) => Some(v.to_raw()),
+            Bound::Excluded(v) => v.to_raw().checked_dec(),
+            Bound::Unbounded => Some(T::Raw::MAX),
+        };
+
+        if let (Some(from), Some(to)) = (from, to) {
+            if from <= to {
+                let res;
+                unsafe {
+                    res = T::Raw::find_range(self.handle, from, to, found.handle);
+                }
+                _check_res(res);
+            }
+        }
+
+        found
+    }
+
+    /// Find the elements equal to any of `values`.
+    pub fn find_any_of(&self, values: &[T]) -> BVector {
+        let found = BVector::with_capacity(self.len());
+
+        let mut raw: Vec<T::Raw> = values.iter().map(|v| v.to_raw()).collect();
+        raw.sort_unstable();
+        raw.dedup();
+
+        let res;
+        unsafe {
+            res = T::Raw::find_any_of(self.handle, raw.as_ptr(), raw.len(), found.handle);
+        }
+        _check_res(res);
+
+        found
+    }
+
+    /// Find the NULL elements.
+    pub fn find_null(&self) -> BVector {
+        let found = BVector::with_capacity(self.len());
+
+        let res;
+        unsafe {
+            res = T::Raw::find_null(self.handle, found.handle);
+        }
+        _check_res(res);
+
+        found
+    }
+}
+
+impl StrSparseVector {
+    /// Create a [`StrScanner`] over the vector, to run many searches.
+    pub fn scanner(&self) -> StrScanner<'_> {
+        StrScanner::new(self)
+    }
+
+    /// Find the strings equal to `value` (case-sensitive).
+    ///
+    /// **Panics** if `value` contains a NUL byte or is longer than
+    /// [`MAX_STR_LEN`].
+    ///
+    /// [`MAX_STR_LEN`]: StrSparseVector::MAX_STR_LEN
+    pub fn find_eq_str(&self, value: &str) -> BVector {
+        self.scanner().find_eq(value)
+    }
+
+    /// Find the strings starting with `prefix` (case-sensitive).
+    ///
+    /// **Panics** if `prefix` contains a NUL byte or is longer than
+    /// [`MAX_STR_LEN`].
+    ///
+    /// [`MAX_STR_LEN`]: StrSparseVector::MAX_STR_LEN
+    pub fn find_prefix(&self, prefix: &str) -> BVector {
+        self.scanner().find_prefix(prefix)
+    }
+
+    /// Binary search `value` in a sorted vector (case-sensitive), see
+    /// [`StrScanner::bfind`].
+    pub fn bfind(&self, value: &str) -> Result<usize, usize> {
+        self.scanner().bfind(value)
+    }
+}
+
+/// A prepared search over a [`StrSparseVector`].
+///
+/// Setting up a search has a cost, so a scanner should be reused to run
+/// many queries over the same vector. Searches are case-sensitive.
+pub struct StrScanner<'a> {
+    handle: *mut c_void,
+    sv: &'a StrSparseVector,
+}
+
+impl<'a> StrScanner<'a> {
+    /// Create a scanner over `sv`.
+    pub fn new(sv: &'a StrSparseVector) -> StrScanner<'a> {
+        let mut handle = ptr::null_mut();
+
+        let res;
+        unsafe {
+            res = bitmagic_sys::BM_str_sparse_vector_scanner_construct(&mut handle);
+        }
+        _check_res(res);
+
+        let scanner = StrScanner { handle, sv };
+        scanner.bind(false);
+        scanner
+    }
+
+    /// Index the vector to speed up [`bfind`]. The vector must be sorted.
+    ///
+    /// [`bfind`]: StrScanner::bfind
+    pub fn sorted(self) -> StrScanner<'a> {
+        self.bind(true);
+        self
+    }
+
+    /// Find the strings equal to `value`.
+    ///
+    /// The result has the length of the vector as its capacity.
+    ///
+    /// **Panics** if `value` contains a NUL byte or is longer than
+    /// [`MAX_STR_LEN`].
+    ///
+    /// [`MAX_STR_LEN`]: StrSparseVector::MAX_STR_LEN
+    pub fn find_eq(&self, value: &str) -> BVector {
+        let c_value = to_c_str(value);
+        let found = BVector::with_capacity(self.sv.len());
+
+        let res;
+        unsafe {
+            res = bitmagic_sys::BM_str_sparse_vector_scanner_find_eq(
+                self.handle,
+                c_value.as_ptr(),
+                found.handle,
+            );
+        }
+        _check_res(res);
+
+        found
+    }
+
+    /// Find the strings starting with `prefix`.
+    ///
+    /// The result has the length of the vector as its capacity.
+    ///
+    /// **Panics** if `prefix` contains a NUL byte or is longer than
+    /// [`MAX_STR_LEN`].
+    ///
+    /// [`MAX_STR_LEN`]: StrSparseVector::MAX_STR_LEN
+    pub fn find_prefix(&self, prefix: &str) -> BVector {
+        let c_prefix = to_c_str(prefix);
+        let found = BVector::with_capacity(self.sv.len());
+
+        let res;
+        unsafe {
+            res = bitmagic_sys::BM_str_sparse_vector_scanner_find_prefix(
+                self.handle,
+                c_prefix.as_ptr(),
+                found.handle,
+            );
+        }
+        _check_res(res);
+
+        found
+    }
+
+    /// Binary search `value` in a sorted vector.
+    ///
+    /// Like [`slice::binary_search`], returns `Ok` with the position of a
+    /// string equal to `value`, or `Err` with the position where `value`
+    /// could be inserted keeping the vector sorted. In both cases, this is
+    /// the position of the first string that is not less than `value`.
+    ///
+    /// The vector must be sorted byte-wise, with NULLs treated as empty
+    /// strings. The result is unspecified otherwise.
+    ///
+    /// **Panics** if `value` contains a NUL byte or is longer than
+    /// [`MAX_STR_LEN`].
+    ///
+    /// [`slice::binary_search`]: https://doc.rust-lang.org/std/primitive.slice.html#method.binary_search
+    /// [`MAX_STR_LEN`]: StrSparseVector::MAX_STR_LEN
+    pub fn bfind(&self, value: &str) -> Result<usize, usize> {
+        let c_value = to_c_str(value);
+        let mut pos = 0;
+        let mut found = 0;
+
+        let res;
+        unsafe {
+            res = bitmagic_sys::BM_str_sparse_vector_scanner_lower_bound(
+                self.handle,
+                c_value.as_ptr(),
+                &mut pos,
+                &mut found,
+            );
+        }
+        _check_res(res);
+
+        if found == 1 {
+            Ok(pos as usize)
+        } else {
+            Err(pos as usize)
+        }
+    }
+
+    fn bind(&self, sorted: bool) {
+        let res;
+        unsafe {
+            res = bitmagic_sys::BM_str_sparse_vector_scanner_bind(
+                self.handle,
+                self.sv.handle,
+                sorted as i32,
+            );
+        }
+        _check_res(res);
+    }
+}
+
+impl<'a> Drop for StrScanner<'a> {
+    fn drop(&mut self) {
+        unsafe {
+            bitmagic_sys::BM_str_sparse_vector_scanner_free(self.handle);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::ops::Bound;
+
+    use crate::{BVector, SparseVector, StrSparseVector};
+
+    fn ones(bv: &BVector) -> Vec<usize> {
+        bv.ones().collect()
+    }
+
+    fn sample() -> SparseVector<u32> {
+        let mut sv = SparseVector::new();
+        sv.import_from_slice(0, &[5, 0, 7, 5, 100, 0]);
+        sv.set_null(1);
+        sv.set_null(8);
+        sv
+    }
+
+    #[test]
+    fn find_eq() {
+        let sv = sample();
+
+        assert_eq!(ones(&sv.find_eq(5)), vec![0, 3]);
+        assert_eq!(ones(&sv.find_eq(0)), vec![5]);
+        assert_eq!(ones(&sv.find_eq(42)), vec![]);
+    }
+
+    #[test]
+    fn find_range() {
+        let sv = sample();
+
+        assert_eq!(ones(&sv.find_range(5..100)), vec![0, 2, 3]);
+        assert_eq!(ones(&sv.find_range(5..=100)), vec![0, 2, 3, 4]);
+        assert_eq!(ones(&sv.find_range(..6)), vec![0, 3, 5]);
+        assert_eq!(ones(&sv.find_range(..)), vec![0, 2, 3, 4, 5]);
+        assert_eq!(ones(&sv.find_range(..0)), vec![]);
+        assert_eq!(
+            ones(&sv.find_range((Bound::Excluded(5), Bound::Included(7)))),
+            vec![2]
+        );
+        assert_eq!(
+            ones(&sv.find_range((Bound::Excluded(u32::MAX), Bound::Unbounded))),
+            vec![]
+        );
+    }
+
+    #[test]
+    fn find_range_signed() {
+        let mut sv = SparseVector::<i64>::new();
+        sv.import_from_slice(0, &[-10, 0, 10, i64::MIN, i64::MAX, -1]);
+        sv.set_null(8);
+
+        assert_eq!(ones(&sv.find_range(-10..10)), vec![0, 1, 5]);
+        assert_eq!(ones(&sv.find_range(..0)), vec![0, 3, 5]);
+        assert_eq!(ones(&sv.find_range(1..)), vec![2, 4]);
+        assert_eq!(ones(&sv.find_eq(i64::MIN)), vec![3]);
+    }
+
+    #[test]
+    fn find_any_of() {
+        let sv = sample();
+
+        assert_eq!(ones(&sv.find_any_of(&[100, 7, 7, 3])), vec![2, 4]);
+        assert_eq!(ones(&sv.find_any_of(&[])), vec![]);
+    }
+
+    #[test]
+    fn find_null() {
+        let sv = sample();
+
+        assert_eq!(ones(&sv.find_null()), vec![1, 6, 7, 8]);
+        assert_eq!(ones(&SparseVector::<u32>::new().find_null()), vec![]);
+    }
+
+    #[test]
+    fn compose() {
+        let sv = sample();
+
+        let found = &(&sv.find_eq(5) | &sv.find_eq(7)) & &sv.find_range(6..);
+        assert_eq!(ones(&found), vec![2]);
+        assert_eq!(found.len(), sv.len());
+        assert!(found.contains(2));
+    }
+
+    fn genes() -> StrSparseVector {
+        let mut sv = StrSparseVector::new();
+        for name in &["BRCA1", "BRCA2", "brca2", "TP53", "Tp53-as1", "", "BRCA2"] {
+            sv.push(name);
+        }
+        sv.set(8, "TP53");
+        sv
+    }
+
+    #[test]
+    fn find_eq_str() {
+        let sv = genes();
+
+        assert_eq!(ones(&sv.find_eq_str("BRCA2")), vec![1, 6]);
+        assert_eq!(ones(&sv.find_eq_str("TP53")), vec![3, 8]);
+        assert_eq!(ones(&sv.find_eq_str("")), vec![5]);
+        assert_eq!(ones(&sv.find_eq_str("MYC")), vec![]);
+
+        let found = sv.scanner().find_eq("brca2");
+        assert_eq!(ones(&found), vec![2]);
+        assert_eq!(found.len(), sv.len());
+    }
+
+    #[test]
+    fn find_prefix() {
+        let sv = genes();
+
+        assert_eq!(ones(&sv.find_prefix("BRCA")), vec![0, 1, 6]);
+        assert_eq!(ones(&sv.find_prefix("T")), vec![3, 4, 8]);
+        assert_eq!(ones(&sv.find_prefix("")), vec![0, 1, 2, 3, 4, 5, 6, 8]);
+
+        let found = sv.scanner().find_prefix("Tp");
+        assert_eq!(ones(&found), vec![4]);
+        assert_eq!(found.len(), sv.len());
+    }
+
+    #[test]
+    fn bfind() {
+        let mut sv = StrSparseVector::new();
+        for name in &["ACTB", "BRCA1", "BRCA2", "EGFR", "TP53"] {
+            sv.push(name);
+        }
+
+        assert_eq!(sv.bfind("BRCA2"), Ok(2));
+        assert_eq!(sv.bfind("ACTA"), Err(0));
+        assert_eq!(sv.bfind("CD4"), Err(3));
+        assert_eq!(sv.bfind("ZZZ"), Err(5));
+
+        let scanner = sv.scanner().sorted();
+        for (idx, name) in ["ACTB", "BRCA1", "BRCA2", "EGFR", "TP53"]
+            .iter()
+            .enumerate()
+        {
+            assert_eq!(scanner.bfind(name), Ok(idx));
+        }
+    }
+}
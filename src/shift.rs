@@ -0,0 +1,143 @@
+use crate::{_check_res, BVector};
+
+impl BVector {
+    /// Move every bit `n` positions towards the end: bit `i` becomes bit
+    /// `i + n`, and the first `n` bits are disabled.
+    ///
+    /// The capacity stays the same, so bits moved past the end are dropped.
+    pub fn shift_right(&mut self, n: usize) {
+        let n = n.min(self.bit_limit());
+
+        let res;
+        unsafe {
+            res = bitmagic_sys::BM_bvector_shift_right(self.handle, n as u32);
+        }
+        _check_res(res);
+    }
+
+    /// Move every bit `n` positions towards the start: bit `i` becomes bit
+    /// `i - n`, and the first `n` bits are dropped.
+    ///
+    /// The capacity stays the same, so the last `n` bits are disabled.
+    pub fn shift_left(&mut self, n: usize) {
+        let n = n.min(self.bit_limit());
+
+        let res;
+        unsafe {
+            res = bitmagic_sys::BM_bvector_shift_left(self.handle, n as u32);
+        }
+        _check_res(res);
+    }
+
+    /// Insert a bit set to `enabled` at `pos`, moving the bits from `pos`
+    /// onwards one position towards the end.
+    ///
+    /// The capacity stays the same, so the last bit is dropped.
+    ///
+    /// **Panics** if **pos** is out of bounds.
+    pub fn insert_bit(&mut self, pos: usize, enabled: bool) {
+        assert!(pos < self.bit_limit(), "bit out of bounds: {}", pos);
+
+        let res;
+        unsafe {
+            res = bitmagic_sys::BM_bvector_insert_bit(self.handle, pos as u32, enabled as i32);
+        }
+        _check_res(res);
+    }
+
+    /// Remove the bit at `pos`, moving the bits after it one position
+    /// towards the start.
+    ///
+    /// The capacity stays the same, so the last bit is disabled.
+    ///
+    /// **Panics** if **pos** is out of bounds.
+    pub fn erase_bit(&mut self, pos: usize) {
+        assert!(pos < self.bit_limit(), "bit out of bounds: {}", pos);
+
+        let res;
+        unsafe {
+            res = bitmagic_sys::BM_bvector_erase_bit(self.handle, pos as u32);
+        }
+        _check_res(res);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::BVector;
+
+    fn bvector(capacity: usize, bits: &[usize]) -> BVector {
+        let mut bv = BVector::with_capacity(capacity);
+        for &bit in bits {
+            bv.insert(bit);
+        }
+        bv
+    }
+
+    fn ones(bv: &BVector) -> Vec<usize> {
+        bv.ones().collect()
+    }
+
+    #[test]
+    fn shift_right() {
+        let mut bv = bvector(100, &[0, 1, 50, 98]);
+
+        bv.shift_right(1);
+        assert_eq!(ones(&bv), vec![1, 2, 51, 99]);
+        assert_eq!(bv.len(), 100);
+
+        bv.shift_right(40);
+        assert_eq!(ones(&bv), vec![41, 42, 91]);
+        assert_eq!(bv.len(), 100);
+
+        bv.shift_right(1000);
+        assert_eq!(ones(&bv), Vec::<usize>::new());
+    }
+
+    #[test]
+    fn shift_left() {
+        let mut bv = bvector(100, &[0, 1, 50, 98, 99]);
+
+        bv.shift_left(1);
+        assert_eq!(ones(&bv), vec![0, 49, 97, 98]);
+        assert_eq!(bv.len(), 100);
+
+        bv.shift_left(40);
+        assert_eq!(ones(&bv), vec![9, 57, 58]);
+        assert_eq!(bv.len(), 100);
+    }
+
+    #[test]
+    fn shift_unbounded() {
+        let mut bv = BVector::new();
+        for bit in 3..6 {
+            bv.insert(bit);
+        }
+
+        bv.shift_right(100_000);
+        bv.shift_left(99_990);
+        assert_eq!(ones(&bv), vec![13, 14, 15]);
+    }
+
+    #[test]
+    fn insert_and_erase_bit() {
+        let mut bv = bvector(10, &[1, 3, 9]);
+
+        bv.insert_bit(2, true);
+        assert_eq!(ones(&bv), vec![1, 2, 4]);
+        assert_eq!(bv.len(), 10);
+
+        bv.insert_bit(0, false);
+        assert_eq!(ones(&bv), vec![2, 3, 5]);
+
+        bv.erase_bit(3);
+        assert_eq!(ones(&bv), vec![2, 4]);
+        assert_eq!(bv.len(), 10);
+    }
+
+    #[should_panic]
+    #[test]
+    fn insert_bit_out_of_bounds() {
+        bvector(10, &[]).insert_bit(10, true);
+    }
+}
@@ -0,0 +1,123 @@
+//! Sorting through `BVector`s.
+//!
+//! A set of `u32` is a `BVector` with the matching bits enabled, and
+//! enumerating its bits yields the values sorted and deduplicated. The
+//! bit-planes of a [`SparseVector`] likewise give its sort order without
+//! comparing values.
+//!
+//! [`SparseVector`]: crate::SparseVector
+
+use crate::aggregate::Group;
+use crate::{BVector, RankSelect, SparseValue, SparseVector};
+
+/// Sort `values` and remove the duplicates, by building a `BVector` out of
+/// them and exporting its enabled bits.
+pub fn sort_unique_u32(values: &mut Vec<u32>) {
+    // u32::MAX is past the last bit a BVector can address
+    let has_max = take_max(values);
+
+    *values = from_ids(values).to_ids();
+
+    if has_max {
+        values.push(u32::MAX);
+    }
+}
+
+/// Replace every value by its dense rank, that is the number of distinct
+/// values smaller than it. The ranks keep the order of the values, and
+/// range from 0 to the number of distinct values (excluded).
+pub fn dense_rank_u32(values: &[u32]) -> Vec<u32> {
+    let mut distinct = values.to_vec();
+    take_max(&mut distinct);
+
+    let ranks = RankSelect::new(from_ids(&distinct));
+    let max_rank = ranks.as_bvector().count_all();
+
+    values
+        .iter()
+        .map(|&value| {
+            if value == u32::MAX {
+                max_rank as u32
+            } else {
+                ranks.rank1(value as usize) as u32
+            }
+        })
+        .collect()
+}
+
+impl<T: SparseValue> SparseVector<T> {
+    /// The permutation that sorts the vector: the rows in increasing value
+    /// order, then the NULL rows. Rows with equal values stay in increasing
+    /// row order.
+    pub fn argsort(&self) -> Vec<usize> {
+        let mut order = Vec::with_capacity(self.len());
+
+        self.for_each_group(&self.not_null(), |group| match group {
+            Group::Equal(_, rows, _) => order.extend(rows.to_ids().iter().map(|&row| row as usize)),
+            Group::Decoded(pairs) => order.extend(pairs.iter().map(|&(_, row)| row)),
+        });
+        order.extend(self.find_null().to_ids().iter().map(|&row| row as usize));
+
+        order
+    }
+}
+
+/// Remove `u32::MAX` from `values`, returning whether it was there.
+fn take_max(values: &mut Vec<u32>) -> bool {
+    let len = values.len();
+    values.retain(|&value| value != u32::MAX);
+    values.len() != len
+}
+
+/// Build a `BVector` with the bits in `ids` enabled.
+fn from_ids(ids: &[u32]) -> BVector {
+    let mut bv = BVector::new();
+    bv.insert_many(ids);
+    bv
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{dense_rank_u32, sort_unique_u32};
+    use crate::SparseVector;
+
+    #[test]
+    fn sort_unique() {
+        let mut values = vec![7, 3, u32::MAX, 3, 0, 1 << 20, 7, u32::MAX - 1];
+        sort_unique_u32(&mut values);
+        assert_eq!(values, vec![0, 3, 7, 1 << 20, u32::MAX - 1, u32::MAX]);
+
+        let mut empty = vec![];
+        sort_unique_u32(&mut empty);
+        assert!(empty.is_empty());
+    }
+
+    #[test]
+    fn dense_rank() {
+        let values = vec![30, 10, u32::MAX, 10, 0, 30, 20];
+        assert_eq!(dense_rank_u32(&values), vec![3, 1, 4, 1, 0, 3, 2]);
+    }
+
+    #[test]
+    fn argsort() {
+        let mut sv = SparseVector::<i64>::new();
+        sv.import_from_slice(0, &[5, -2, 5, 0, 9, -2]);
+        sv.set_null(1);
+        sv.set_null(7);
+
+        assert_eq!(sv.argsort(), vec![5, 3, 0, 2, 4, 1, 6, 7]);
+    }
+
+    #[test]
+    fn argsort_large_groups() {
+        let values: Vec<u32> = (0..1000).map(|i| (i * 7919) % 100).collect();
+
+        let mut sv = SparseVector::new();
+        sv.import_from_slice(0, &values);
+
+        let mut expected: Vec<usize> = (0..values.len()).collect();
+        expected.sort_by_key(|&row| values[row]);
+
+        assert_eq!(sv.argsort(), expected);
+    }
+}
@@ -0,0 +1,872 @@
+use std::error::Error;
+use std::io::{Read, Write};
+use std::marker::PhantomData;
+use std::os::raw::{c_uchar, c_void};
+use std::ptr;
+
+use crate::conversions::MAX_BITS;
+use crate::fixedbitset_api::IndexRange;
+use crate::{_check_res, init_lib, BVector, BVectorRef};
+
+use self::sealed::RawValue;
+
+pub(crate) mod sealed {
+    use std::os::raw::{c_int, c_uchar, c_void};
+
+    /// Unsigned value types with a BitMagic `sparse_vector` instantiation in
+    /// the libbm extension API, and their FFI entry points.
+    ///
+    /// The functions take a handle to a sparse vector of `Self`, or for the
+    /// `rsc_` ones to a succinct sparse vector of `Self`.
+    pub trait RawValue: Copy + Default + Ord {
+        const MAX: Self;
+        const BITS: u32;
+
+        /// Truncating conversion, to assemble a value out of its bits.
+        fn from_u64(v: u64) -> Self;
+
+        fn checked_inc(self) -> Option<Self>;
+        fn checked_dec(self) -> Option<Self>;
+
+        unsafe fn construct(h: *mut *mut c_void) -> c_int;
+        unsafe fn construct_copy(h: *mut *mut c_void, hfrom: *mut c_void) -> c_int;
+        unsafe fn free(h: *mut c_void) -> c_int;
+        unsafe fn size(h: *mut c_void, psize: *mut u32) -> c_int;
+        unsafe fn push_back(h: *mut c_void, value: Self) -> c_int;
+        unsafe fn set(h: *mut c_void, idx: u32, value: Self) -> c_int;
+        unsafe fn set_null(h: *mut c_void, idx: u32) -> c_int;
+        unsafe fn get(h: *mut c_void, idx: u32, pvalue: *mut Self, pnull: *mut c_int) -> c_int;
+        unsafe fn import(h: *mut c_void, arr: *const Self, arr_size: usize, offset: u32) -> c_int;
+        unsafe fn decode(
+            h: *mut c_void,
+            arr: *mut Self,
+            from: u32,
+            size: usize,
+            pdecoded: *mut usize,
+        ) -> c_int;
+        unsafe fn optimize(h: *mut c_void) -> c_int;
+        unsafe fn serialize(h: *mut c_void, pbuf: *mut *mut c_uchar, psize: *mut usize) -> c_int;
+        unsafe fn deserialize(h: *mut c_void, buf: *const c_uchar, buf_size: usize) -> c_int;
+        unsafe fn get_null_bvector(h: *mut c_void, pbv: *mut *mut c_void) -> c_int;
+        unsafe fn get_plane(h: *mut c_void, i: u32, pbv: *mut *mut c_void) -> c_int;
+
+        unsafe fn find_eq(h: *mut c_void, value: Self, hbv: *mut c_void) -> c_int;
+        unsafe fn find_range(h: *mut c_void, from: Self, to: Self, hbv: *mut c_void) -> c_int;
+        unsafe fn find_any_of(
+            h: *mut c_void,
+            values: *const Self,
+            values_size: usize,
+            hbv: *mut c_void,
+        ) -> c_int;
+        unsafe fn find_null(h: *mut c_void, hbv: *mut c_void) -> c_int;
+
+        unsafe fn rsc_construct(h: *mut *mut c_void) -> c_int;
+        unsafe fn rsc_construct_copy(h: *mut *mut c_void, hfrom: *mut c_void) -> c_int;
+        unsafe fn rsc_free(h: *mut c_void) -> c_int;
+        unsafe fn rsc_size(h: *mut c_void, psize: *mut u32) -> c_int;
+        unsafe fn rsc_push_back(h: *mut c_void, idx: u32, value: Self) -> c_int;
+        unsafe fn rsc_sync(h: *mut c_void) -> c_int;
+        unsafe fn rsc_load_from(h: *mut c_void, hsv: *mut c_void) -> c_int;
+        unsafe fn rsc_load_to(h: *mut c_void, hsv: *mut c_void) -> c_int;
+        unsafe fn rsc_get(h: *mut c_void, idx: u32, pvalue: *mut Self, pnull: *mut c_int) -> c_int;
+        unsafe fn rsc_decode(
+            h: *mut c_void,
+            arr: *mut Self,
+            from: u32,
+            size: usize,
+            pdecoded: *mut usize,
+        ) -> c_int;
+        unsafe fn rsc_optimize(h: *mut c_void) -> c_int;
+        unsafe fn rsc_serialize(
+            h: *mut c_void,
+            pbuf: *mut *mut c_uchar,
+            psize: *mut usize,
+        ) -> c_int;
+        unsafe fn rsc_deserialize(h: *mut c_void, buf: *const c_uchar, buf_size: usize) -> c_int;
+        unsafe fn rsc_get_null_bvector(h: *mut c_void, pbv: *mut *mut c_void) -> c_int;
+    }
+
+    macro_rules! raw_value {
+        ($raw:ty {
+            construct: $construct:ident,
+            construct_copy: $construct_copy:ident,
+            free: $free:ident,
+            size: $size:ident,
+            push_back: $push_back:ident,
+            set: $set:ident,
+            set_null: $set_null:ident,
+            get: $get:ident,
+            import: $import:ident,
+            decode: $decode:ident,
+            optimize: $optimize:ident,
+            serialize: $serialize:ident,
+            deserialize: $deserialize:ident,
+            get_null_bvector: $get_null_bvector:ident,
+            get_plane: $get_plane:ident,
+            find_eq: $find_eq:ident,
+            find_range: $find_range:ident,
+            find_any_of: $find_any_of:ident,
+            find_null: $find_null:ident,
+            rsc_construct: $rsc_construct:ident,
+            rsc_construct_copy: $rsc_construct_copy:ident,
+            rsc_free: $rsc_free:ident,
+            rsc_size: $rsc_size:ident,
+            rsc_push_back: $rsc_push_back:ident,
+            rsc_sync: $rsc_sync:ident,
+            rsc_load_from: $rsc_load_from:ident,
+            rsc_load_to: $rsc_load_to:ident,
+            rsc_get: $rsc_get:ident,
+            rsc_decode: $rsc_decode:ident,
+            rsc_optimize: $rsc_optimize:ident,
+            rsc_serialize: $rsc_serialize:ident,
+            rsc_deserialize: $rsc_deserialize:ident,
+            rsc_get_null_bvector: $rsc_get_null_bvector:ident,
+        }) => {
+            impl RawValue for $raw {
+                const MAX: Self = <$raw>::MAX;
+                const BITS: u32 = (std::mem::size_of::<$raw>() * 8) as u32;
+
+                fn from_u64(v: u64) -> Self {
+                    v as $raw
+                }
+
+                fn checked_inc(self) -> Option<Self> {
+                    self.checked_add(1)
+                }
+                fn checked_dec(self) -> Option<Self> {
+                    self.checked_sub(1)
+                }
+
+                unsafe fn construct(h: *mut *mut c_void) -> c_int {
+                    bitmagic_sys::$construct(h)
+                }
+                unsafe fn construct_copy(h: *mut *mut c_void, hfrom: *mut c_void) -> c_int {
+                    bitmagic_sys::$construct_copy(h, hfrom)
+                }
+                unsafe fn free(h: *mut c_void) -> c_int {
+                    bitmagic_sys::$free(h)
+                }
+                unsafe fn size(h: *mut c_void, psize: *mut u32) -> c_int {
+                    bitmagic_sys::$size(h, psize)
+                }
+                unsafe fn push_back(h: *mut c_void, value: Self) -> c_int {
+                    bitmagic_sys::$push_back(h, value)
+                }
+                unsafe fn set(h: *mut c_void, idx: u32, value: Self) -> c_int {
+                    bitmagic_sys::$set(h, idx, value)
+                }
+                unsafe fn set_null(h: *mut c_void, idx: u32) -> c_int {
+                    bitmagic_sys::$set_null(h, idx)
+                }
+                unsafe fn get(
+                    h: *mut c_void,
+                    idx: u32,
+                    pvalue: *mut Self,
+                    pnull: *mut c_int,
+                ) -> c_int {
+                    bitmagic_sys::$get(h, idx, pvalue, pnull)
+                }
+                unsafe fn import(
+                    h: *mut c_void,
+                    arr: *const Self,
+                    arr_size: usize,
+                    offset: u32,
+                ) -> c_int {
+                    bitmagic_sys::$import(h, arr, arr_size, offset)
+                }
+                unsafe fn decode(
+                    h: *mut c_void,
+                    arr: *mut Self,
+                    from: u32,
+                    size: usize,
+                    pdecoded: *mut usize,
+                ) -> c_int {
+                    bitmagic_sys::$decode(h, arr, from, size, pdecoded)
+                }
+                unsafe fn optimize(h: *mut c_void) -> c_int {
+                    bitmagic_sys::$optimize(h)
+                }
+                unsafe fn serialize(
+                    h: *mut c_void,
+                    pbuf: *mut *mut c_uchar,
+                    psize: *mut usize,
+                ) -> c_int {
+                    bitmagic_sys::$serialize(h, pbuf, psize)
+                }
+                unsafe fn deserialize(
+                    h: *mut c_void,
+                    buf: *const c_uchar,
+                    buf_size: usize,
+                ) -> c_int {
+                    bitmagic_sys::$deserialize(h, buf, buf_size)
+                }
+                unsafe fn get_null_bvector(h: *mut c_void, pbv: *mut *mut c_void) -> c_int {
+                    bitmagic_sys::$get_null_bvector(h, pbv)
+                }
+                unsafe fn get_plane(h: *mut c_void, i: u32, pbv: *mut *mut c_void) -> c_int {
+                    bitmagic_sys::$get_plane(h, i, pbv)
+                }
+
+                unsafe fn find_eq(h: *mut c_void, value: Self, hbv: *mut c_void) -> c_int {
+                    bitmagic_sys::$find_eq(h, value, hbv)
+                }
+                unsafe fn find_range(
+                    h: *mut c_void,
+                    from: Self,
+                    to: Self,
+                    hbv: *mut c_void,
+                ) -> c_int {
+                    bitmagic_sys::$find_range(h, from, to, hbv)
+                }
+                unsafe fn find_any_of(
+                    h: *mut c_void,
+                    values: *const Self,
+                    values_size: usize,
+                    hbv: *mut c_void,
+                ) -> c_int {
+                    bitmagic_sys::$find_any_of(h, values, values_size, hbv)
+                }
+                unsafe fn find_null(h: *mut c_void, hbv: *mut c_void) -> c_int {
+                    bitmagic_sys::$find_null(h, hbv)
+                }
+
+                unsafe fn rsc_construct(h: *mut *mut c_void) -> c_int {
+                    bitmagic_sys::$rsc_construct(h)
+                }
+                unsafe fn rsc_construct_copy(h: *mut *mut c_void, hfrom: *mut c_void) -> c_int {
+                    bitmagic_sys::$rsc_construct_copy(h, hfrom)
+                }
+                unsafe fn rsc_free(h: *mut c_void) -> c_int {
+                    bitmagic_sys::$rsc_free(h)
+                }
+                unsafe fn rsc_size(h: *mut c_void, psize: *mut u32) -> c_int {
+                    bitmagic_sys::$rsc_size(h, psize)
+                }
+                unsafe fn rsc_push_back(h: *mut c_void, idx: u32, value: Self) -> c_int {
+                    bitmagic_sys::$rsc_push_back(h, idx, value)
+                }
+                unsafe fn rsc_sync(h: *mut c_void) -> c_int {
+                    bitmagic_sys::$rsc_sync(h)
+                }
+                unsafe fn rsc_load_from(h: *mut c_void, hsv: *mut c_void) -> c_int {
+                    bitmagic_sys::$rsc_load_from(h, hsv)
+                }
+                unsafe fn rsc_load_to(h: *mut c_void, hsv: *mut c_void) -> c_int {
+                    bitmagic_sys::$rsc_load_to(h, hsv)
+                }
+                unsafe fn rsc_get(
+                    h: *mut c_void,
+                    idx: u32,
+                    pvalue: *mut Self,
+                    pnull: *mut c_int,
+                ) -> c_int {
+                    bitmagic_sys::$rsc_get(h, idx, pvalue, pnull)
+                }
+                unsafe fn rsc_decode(
+                    h: *mut c_void,
+                    arr: *mut Self,
+                    from: u32,
+                    size: usize,
+                    pdecoded: *mut usize,
+                ) -> c_int {
+                    bitmagic_sys::$rsc_decode(h, arr, from, size, pdecoded)
+                }
+                unsafe fn rsc_optimize(h: *mut c_void) -> c_int {
+                    bitmagic_sys::$rsc_optimize(h)
+                }
+                unsafe fn rsc_serialize(
+                    h: *mut c_void,
+                    pbuf: *mut *mut c_uchar,
+                    psize: *mut usize,
+                ) -> c_int {
+                    bitmagic_sys::$rsc_serialize(h, pbuf, psize)
+                }
+                unsafe fn rsc_deserialize(
+                    h: *mut c_void,
+                    buf: *const c_uchar,
+                    buf_size: usize,
+                ) -> c_int {
+                    bitmagic_sys::$rsc_deserialize(h, buf, buf_size)
+                }
+                unsafe fn rsc_get_null_bvector(h: *mut c_void, pbv: *mut *mut c_void) -> c_int {
+                    bitmagic_sys::$rsc_get_null_bvector(h, pbv)
+                }
+            }
+        };
+    }
+
+    raw_value!(u32 {
+        construct: BM_sparse_vector_u32_construct,
+        construct_copy: BM_sparse_vector_u32_construct_copy,
+        free: BM_sparse_vector_u32_free,
+        size: BM_sparse_vector_u32_size,
+        push_back: BM_sparse_vector_u32_push_back,
+        set: BM_sparse_vector_u32_set,
+        set_null: BM_sparse_vector_u32_set_null,
+        get: BM_sparse_vector_u32_get,
+        import: BM_sparse_vector_u32_import,
+        decode: BM_sparse_vector_u32_decode,
+        optimize: BM_sparse_vector_u32_optimize,
+        serialize: BM_sparse_vector_u32_serialize,
+        deserialize: BM_sparse_vector_u32_deserialize,
+        get_null_bvector: BM_sparse_vector_u32_get_null_bvector,
+        get_plane: BM_sparse_vector_u32_get_plane,
+        find_eq: BM_sparse_vector_u32_find_eq,
+        find_range: BM_sparse_vector_u32_find_range,
+        find_any_of: BM_sparse_vector_u32_find_any_of,
+        find_null: BM_sparse_vector_u32_find_null,
+        rsc_construct: BM_rsc_sparse_vector_u32_construct,
+        rsc_construct_copy: BM_rsc_sparse_vector_u32_construct_copy,
+        rsc_free: BM_rsc_sparse_vector_u32_free,
+        rsc_size: BM_rsc_sparse_vector_u32_size,
+        rsc_push_back: BM_rsc_sparse_vector_u32_push_back,
+        rsc_sync: BM_rsc_sparse_vector_u32_sync,
+        rsc_load_from: BM_rsc_sparse_vector_u32_load_from,
+        rsc_load_to: BM_rsc_sparse_vector_u32_load_to,
+        rsc_get: BM_rsc_sparse_vector_u32_get,
+        rsc_decode: BM_rsc_sparse_vector_u32_decode,
+        rsc_optimize: BM_rsc_sparse_vector_u32_optimize,
+        rsc_serialize: BM_rsc_sparse_vector_u32_serialize,
+        rsc_deserialize: BM_rsc_sparse_vector_u32_deserialize,
+        rsc_get_null_bvector: BM_rsc_sparse_vector_u32_get_null_bvector,
+    });
+
+    raw_value!(u64 {
+        construct: BM_sparse_vector_u64_construct,
+        construct_copy: BM_sparse_vector_u64_construct_copy,
+        free: BM_sparse_vector_u64_free,
+        size: BM_sparse_vector_u64_size,
+        push_back: BM_sparse_vector_u64_push_back,
+        set: BM_sparse_vector_u64_set,
+        set_null: BM_sparse_vector_u64_set_null,
+        get: BM_sparse_vector_u64_get,
+        import: BM_sparse_vector_u64_import,
+        decode: BM_sparse_vector_u64_decode,
+        optimize: BM_sparse_vector_u64_optimize,
+        serialize: BM_sparse_vector_u64_serialize,
+        deserialize: BM_sparse_vector_u64_deserialize,
+        get_null_bvector: BM_sparse_vector_u64_get_null_bvector,
+        get_plane: BM_sparse_vector_u64_get_plane,
+        find_eq: BM_sparse_vector_u64_find_eq,
+        find_range: BM_sparse_vector_u64_find_range,
+        find_any_of: BM_sparse_vector_u64_find_any_of,
+        find_null: BM_sparse_vector_u64_find_null,
+        rsc_construct: BM_rsc_sparse_vector_u64_construct,
+        rsc_construct_copy: BM_rsc_sparse_vector_u64_construct_copy,
+        rsc_free: BM_rsc_sparse_vector_u64_free,
+        rsc_size: BM_rsc_sparse_vector_u64_size,
+        rsc_push_back: BM_rsc_sparse_vector_u64_push_back,
+        rsc_sync: BM_rsc_sparse_vector_u64_sync,
+        rsc_load_from: BM_rsc_sparse_vector_u64_load_from,
+        rsc_load_to: BM_rsc_sparse_vector_u64_load_to,
+        rsc_get: BM_rsc_sparse_vector_u64_get,
+        rsc_decode: BM_rsc_sparse_vector_u64_decode,
+        rsc_optimize: BM_rsc_sparse_vector_u64_optimize,
+        rsc_serialize: BM_rsc_sparse_vector_u64_serialize,
+        rsc_deserialize: BM_rsc_sparse_vector_u64_deserialize,
+        rsc_get_null_bvector: BM_rsc_sparse_vector_u64_get_null_bvector,
+    });
+
+    pub trait Sealed {}
+
+    impl Sealed for u32 {}
+    impl Sealed for u64 {}
+    impl Sealed for i32 {}
+    impl Sealed for i64 {}
+}
+
+/// Integer types that can be stored in a [`SparseVector`]: `u32`, `u64`,
+/// `i32` and `i64`.
+///
+/// Signed values are stored with their sign bit flipped, so they keep
+/// their order when compared as unsigned values.
+pub trait SparseValue: Copy + Ord + Into<i128> + sealed::Sealed {
+    #[doc(hidden)]
+    type Raw: sealed::RawValue;
+
+    #[doc(hidden)]
+    fn to_raw(self) -> Self::Raw;
+
+    #[doc(hidden)]
+    fn from_raw(raw: Self::Raw) -> Self;
+}
+
+impl SparseValue for u32 {
+    type Raw = u32;
+
+    fn to_raw(self) -> u32 {
+        self
+    }
+
+    fn from_raw(raw: u32) -> u32 {
+        raw
+    }
+}
+
+impl SparseValue for u64 {
+    type Raw = u64;
+
+    fn to_raw(self) -> u64 {
+        self
+    }
+
+    fn from_raw(raw: u64) -> u64 {
+        raw
+    }
+}
+
+impl SparseValue for i32 {
+    type Raw = u32;
+
+    fn to_raw(self) -> u32 {
+        (self as u32) ^ (1 << 31)
+    }
+
+    fn from_raw(raw: u32) -> i32 {
+        (raw ^ (1 << 31)) as i32
+    }
+}
+
+impl SparseValue for i64 {
+    type Raw = u64;
+
+    fn to_raw(self) -> u64 {
+        (self as u64) ^ (1 << 63)
+    }
+
+    fn from_raw(raw: u64) -> i64 {
+        (raw ^ (1 << 63)) as i64
+    }
+}
+
+/// A compressed vector of integers, stored as bit-planes
+/// (BitMagic `sparse_vector`).
+///
+/// Elements can be NULL (not set): elements are NULL until a value is
+/// assigned to them, and the vector grows with NULLs when a value is set
+/// past its end. The NULL bit-plane is available through [`not_null`].
+///
+/// [`not_null`]: SparseVector::not_null
+pub struct SparseVector<T: SparseValue> {
+    pub(crate) handle: *mut c_void,
+    _marker: PhantomData<T>,
+}
+
+// Like `BVector`, the handle is uniquely owned and not tied to a thread.
+unsafe impl<T: SparseValue> Send for SparseVector<T> {}
+
+impl<T: SparseValue> SparseVector<T> {
+    /// Create a new, empty `SparseVector`.
+    pub fn new() -> SparseVector<T> {
+        init_lib();
+        let mut handle = ptr::null_mut();
+
+        let res;
+        unsafe {
+            res = T::Raw::construct(&mut handle);
+        }
+        _check_res(res);
+
+        SparseVector::from_handle(handle)
+    }
+
+    fn from_handle(handle: *mut c_void) -> SparseVector<T> {
+        SparseVector {
+            handle,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Number of elements, NULL or not.
+    pub fn len(&self) -> usize {
+        let mut psize = 0;
+
+        let res;
+        unsafe {
+            res = T::Raw::size(self.handle, &mut psize);
+        }
+        _check_res(res);
+
+        psize as usize
+    }
+
+    /// Return `true` if the vector has no elements.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Append `value` to the vector.
+    ///
+    /// **Panics** if the vector is full.
+    pub fn push(&mut self, value: T) {
+        assert!(self.len() < MAX_BITS, "SparseVector is full");
+
+        let res;
+        unsafe {
+            res = T::Raw::push_back(self.handle, value.to_raw());
+        }
+        _check_res(res);
+    }
+
+    /// Set the element at `idx` to `value`, growing the vector with NULLs
+    /// if `idx` is past its end.
+    ///
+    /// **Panics** if `idx` is out of the addressable range.
+    pub fn set(&mut self, idx: usize, value: T) {
+        assert!(idx < MAX_BITS, "index out of bounds: {}", idx);
+
+        let res;
+        unsafe {
+            res = T::Raw::set(self.handle, idx as u32, value.to_raw());
+        }
+        _check_res(res);
+    }
+
+    /// Set the element at `idx` to NULL, growing the vector with NULLs if
+    /// `idx` is past its end.
+    ///
+    /// **Panics** if `idx` is out of the addressable range.
+    pub fn set_null(&mut self, idx: usize) {
+        assert!(idx < MAX_BITS, "index out of bounds: {}", idx);
+
+        let res;
+        unsafe {
+            res = T::Raw::set_null(self.handle, idx as u32);
+        }
+        _check_res(res);
+    }
+
+    /// Value of the element at `idx`, or `None` if it is NULL or `idx` is
+    /// past the end of the vector.
+    pub fn get(&self, idx: usize) -> Option<T> {
+        if idx >= self.len() {
+            return None;
+        }
+
+        let mut value = T::Raw::default();
+        let mut null = 0;
+
+        let res;
+        unsafe {
+            res = T::Raw::get(self.handle, idx as u32, &mut value, &mut null);
+        }
+        _check_res(res);
+
+        if null == 0 {
+            Some(T::from_raw(value))
+        } else {
+            None
+        }
+    }
+
+    /// Set the elements starting at `offset` to `values`, growing the
+    /// vector with NULLs if needed.
+    ///
+    /// This is much faster than setting the elements one at a time.
+    ///
+    /// **Panics** if the values extend out of the addressable range.
+    pub fn import_from_slice(&mut self, offset: usize, values: &[T]) {
+        assert!(
+            offset + values.len() <= MAX_BITS,
+            "values extend out of the addressable range"
+        );
+
+        let raw: Vec<T::Raw> = values.iter().map(|v| v.to_raw()).collect();
+
+        let res;
+        unsafe {
+            res = T::Raw::import(self.handle, raw.as_ptr(), raw.len(), offset as u32);
+        }
+        _check_res(res);
+    }
+
+    /// Decode the elements in the given range, with `None` for NULLs.
+    ///
+    /// Use `..` to decode the whole vector.
+    ///
+    /// **Panics** if the range extends past the end of the vector.
+    pub fn decode_range<R: IndexRange>(&self, range: R) -> Vec<Option<T>> {
+        let len = self.len();
+        let start = range.start().unwrap_or(0);
+        let end = range.end().unwrap_or(len);
+        assert!(end <= len, "Range extends past the end of the vector");
+        assert!(start <= end);
+
+        if start == end {
+            return vec![];
+        }
+
+        let mut raw = vec![T::Raw::default(); end - start];
+        let mut decoded = 0;
+
+        let res;
+        unsafe {
+            res = T::Raw::decode(
+                self.handle,
+                raw.as_mut_ptr(),
+                start as u32,
+                raw.len(),
+                &mut decoded,
+            );
+        }
+        _check_res(res);
+
+        with_nulls(&self.not_null(), start, &raw)
+    }
+
+    /// Optimize the memory layout of the vector.
+    pub fn optimize(&mut self) {
+        let res;
+        unsafe {
+            res = T::Raw::optimize(self.handle);
+        }
+        _check_res(res);
+    }
+
+    /// The NULL bit-plane: its enabled bits are the elements that are not
+    /// NULL.
+    pub fn not_null(&self) -> BVectorRef<'_> {
+        let mut bv = ptr::null_mut();
+
+        let res;
+        unsafe {
+            res = T::Raw::get_null_bvector(self.handle, &mut bv);
+        }
+        _check_res(res);
+
+        unsafe { BVectorRef::from_handle(bv) }
+    }
+
+    /// Serialize sparse vector
+    pub fn serialize<W>(&self, wtr: W) -> Result<(), Box<dyn Error>>
+    where
+        W: Write,
+    {
+        let mut buf = ptr::null_mut();
+        let mut size = 0;
+
+        let res;
+        unsafe {
+            res = T::Raw::serialize(self.handle, &mut buf, &mut size);
+        }
+        _check_res(res);
+
+        write_ext_buffer(buf, size, wtr)
+    }
+
+    /// Deserialize sparse vector
+    pub fn deserialize<R>(mut rdr: R) -> Result<Self, Box<dyn Error>>
+    where
+        R: Read,
+    {
+        let mut buf = vec![];
+        rdr.read_to_end(&mut buf)?;
+
+        let snew = SparseVector::<T>::new();
+
+        let res;
+        unsafe {
+            res = T::Raw::deserialize(snew.handle, buf.as_ptr(), buf.len());
+        }
+
+        if res != bitmagic_sys::BM_OK as i32 {
+            return Err("invalid serialized SparseVector".into());
+        }
+
+        Ok(snew)
+    }
+}
+
+/// Convert raw values decoded from position `start` onwards, with `None` for
+/// the positions that are not enabled in `not_null`.
+pub(crate) fn with_nulls<T: SparseValue>(
+    not_null: &BVector,
+    start: usize,
+    raw: &[T::Raw],
+) -> Vec<Option<T>> {
+    let mut values = vec![None; raw.len()];
+    if raw.is_empty() {
+        return values;
+    }
+
+    let last = start + raw.len() - 1;
+    not_null.for_each_run_from(start as u32, |first, run_last| {
+        let (first, run_last) = (first as usize, run_last as usize);
+        if first > last {
+            return false;
+        }
+        for i in first - start..=run_last.min(last) - start {
+            values[i] = Some(T::from_raw(raw[i]));
+        }
+        true
+    });
+
+    values
+}
+
+/// Write a buffer allocated by the libbm extension API, then release it.
+pub(crate) fn write_ext_buffer<W>(
+    buf: *mut c_uchar,
+    size: usize,
+    mut wtr: W,
+) -> Result<(), Box<dyn Error>>
+where
+    W: Write,
+{
+    if buf.is_null() {
+        return Err("failed to serialize sparse vector".into());
+    }
+
+    let written = unsafe { wtr.write_all(std::slice::from_raw_parts(buf, size)) };
+    unsafe {
+        bitmagic_sys::BM_ext_buffer_free(buf as *mut c_void);
+    }
+    written?;
+
+    Ok(())
+}
+
+impl<T: SparseValue> Default for SparseVector<T> {
+    fn default() -> Self {
+        SparseVector::new()
+    }
+}
+
+impl<T: SparseValue> Clone for SparseVector<T> {
+    fn clone(&self) -> Self {
+        let mut handle = ptr::null_mut();
+
+        let res;
+        unsafe {
+            res = T::Raw::construct_copy(&mut handle, self.handle);
+        }
+        _check_res(res);
+
+        SparseVector::from_handle(handle)
+    }
+}
+
+impl<T: SparseValue> Drop for SparseVector<T> {
+    fn drop(&mut self) {
+        unsafe {
+            T::Raw::free(self.handle);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::SparseVector;
+    use crate::BVector;
+
+    #[test]
+    fn push_set_get() {
+        let mut sv = SparseVector::<u32>::new();
+        assert!(sv.is_empty());
+
+        sv.push(10);
+        sv.push(0);
+        sv.set(5, 50);
+
+        assert_eq!(sv.len(), 6);
+        assert_eq!(sv.get(0), Some(10));
+        assert_eq!(sv.get(1), Some(0));
+        assert_eq!(sv.get(2), None);
+        assert_eq!(sv.get(5), Some(50));
+        assert_eq!(sv.get(6), None);
+
+        sv.set_null(0);
+        assert_eq!(sv.get(0), None);
+        sv.set_null(9);
+        assert_eq!(sv.len(), 10);
+    }
+
+    #[test]
+    fn signed_values() {
+        let mut sv = SparseVector::<i64>::new();
+        sv.import_from_slice(0, &[-3, 0, i64::MIN, i64::MAX, 7]);
+        sv.set_null(1);
+
+        assert_eq!(
+            sv.decode_range(..),
+            vec![Some(-3), None, Some(i64::MIN), Some(i64::MAX), Some(7)]
+        );
+        assert_eq!(sv.get(0), Some(-3));
+    }
+
+    #[test]
+    fn import_and_decode() {
+        let values: Vec<u64> = (0..1000).map(|i| i * i).collect();
+
+        let mut sv = SparseVector::new();
+        sv.import_from_slice(100, &values);
+        sv.optimize();
+
+        assert_eq!(sv.len(), 1100);
+        assert_eq!(sv.decode_range(..100), vec![None; 100]);
+
+        let expected: Vec<_> = (0..5)
+            .map(|_| None)
+            .chain(values.into_iter().map(Some))
+            .collect();
+        assert_eq!(sv.decode_range(95..1100), expected);
+
+        assert_eq!(sv.decode_range(500..500), vec![]);
+    }
+
+    #[should_panic]
+    #[test]
+    fn decode_range_oob() {
+        let mut sv = SparseVector::<u32>::new();
+        sv.import_from_slice(0, &[1, 2, 3]);
+        sv.decode_range(1..4);
+    }
+
+    #[test]
+    fn not_null() {
+        let mut sv = SparseVector::<i32>::new();
+        sv.set(3, -1);
+        sv.set(70, 0);
+
+        let not_null = sv.not_null();
+        assert_eq!(not_null.ones().collect::<Vec<_>>(), vec![3, 70]);
+
+        // serialized without optimizing the plane owned by sv
+        let bytes = not_null.serialize_to_vec().unwrap();
+        let copy = BVector::deserialize(bytes.as_slice()).unwrap();
+        assert_eq!(copy.ones().collect::<Vec<_>>(), vec![3, 70]);
+
+        let owned = not_null.clone();
+        let len = sv.len();
+        drop(sv);
+        assert_eq!(owned.count_ones(..len), 2);
+    }
+
+    #[test]
+    fn serde() {
+        let mut sv = SparseVector::<u32>::new();
+        sv.import_from_slice(0, &[1, 20, 300, 4000]);
+        sv.set(10, 5);
+
+        let mut wtr = vec![];
+        sv.serialize(&mut wtr).unwrap();
+
+        let new_sv = SparseVector::<u32>::deserialize(wtr.as_slice()).unwrap();
+        assert_eq!(new_sv.len(), 11);
+        assert_eq!(new_sv.decode_range(..), sv.decode_range(..));
+
+        assert!(SparseVector::<u32>::deserialize(&b""[..]).is_err());
+    }
+
+    #[test]
+    fn clone() {
+        let mut sv = SparseVector::<u64>::new();
+        sv.push(42);
+
+        let mut copy = sv.clone();
+        copy.push(43);
+
+        assert_eq!(sv.len(), 1);
+        assert_eq!(copy.decode_range(..), vec![Some(42), Some(43)]);
+    }
+}
@@ -0,0 +1,468 @@
+use std::error::Error;
+use std::ffi::CString;
+use std::io::{Read, Write};
+use std::marker::PhantomData;
+use std::os::raw::{c_char, c_void};
+use std::ptr;
+
+use crate::conversions::MAX_BITS;
+use crate::sparse_vector::write_ext_buffer;
+use crate::{_check_res, init_lib, BVectorRef, MemoryStats};
+
+/// A compressed vector of short strings, stored as bit-transposed
+/// characters (BitMagic `str_sparse_vector`).
+///
+/// Like [`SparseVector`], elements are NULL until a string is assigned to
+/// them. Strings must not contain NUL bytes, and are at most
+/// [`MAX_STR_LEN`] bytes long.
+///
+/// [`SparseVector`]: crate::SparseVector
+/// [`MAX_STR_LEN`]: StrSparseVector::MAX_STR_LEN
+pub struct StrSparseVector {
+    pub(crate) handle: *mut c_void,
+}
+
+// Like `BVector`, the handle is uniquely owned and not tied to a thread.
+unsafe impl Send for StrSparseVector {}
+
+impl StrSparseVector {
+    /// Maximum length of a string, in bytes.
+    pub const MAX_STR_LEN: usize = bitmagic_sys::BM_STR_SPARSE_VECTOR_MAX_STR as usize - 1;
+
+    /// Create a new, empty `StrSparseVector`.
+    pub fn new() -> StrSparseVector {
+        init_lib();
+        let mut handle = ptr::null_mut();
+
+        let res;
+        unsafe {
+            res = bitmagic_sys::BM_str_sparse_vector_construct(&mut handle);
+        }
+        _check_res(res);
+
+        StrSparseVector { handle }
+    }
+
+    /// Number of elements, NULL or not.
+    pub fn len(&self) -> usize {
+        let mut psize = 0;
+
+        let res;
+        unsafe {
+            res = bitmagic_sys::BM_str_sparse_vector_size(self.handle, &mut psize);
+        }
+        _check_res(res);
+
+        psize as usize
+    }
+
+    /// Return `true` if the vector has no elements.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Append `value` to the vector.
+    ///
+    /// **Panics** if `value` contains a NUL byte or is longer than
+    /// [`MAX_STR_LEN`], or if the vector is full.
+    ///
+    /// [`MAX_STR_LEN`]: StrSparseVector::MAX_STR_LEN
+    pub fn push(&mut self, value: &str) {
+        assert!(self.len() < MAX_BITS, "StrSparseVector is full");
+        let value = to_c_str(value);
+
+        let res;
+        unsafe {
+            res = bitmagic_sys::BM_str_sparse_vector_push_back(self.handle, value.as_ptr());
+        }
+        _check_res(res);
+    }
+
+    /// Set the element at `idx` to `value`, growing the vector with NULLs
+    /// if `idx` is past its end.
+    ///
+    /// **Panics** if `value` contains a NUL byte or is longer than
+    /// [`MAX_STR_LEN`], or if `idx` is out of the addressable range.
+    ///
+    /// [`MAX_STR_LEN`]: StrSparseVector::MAX_STR_LEN
+    pub fn set(&mut self, idx: usize, value: &str) {
+        assert!(idx < MAX_BITS, "index out of bounds: {}", idx);
+        let value = to_c_str(value);
+
+        let res;
+        unsafe {
+            res = bitmagic_sys::BM_str_sparse_vector_set(self.handle, idx as u32, value.as_ptr());
+        }
+        _check_res(res);
+    }
+
+    /// String at `idx`, or `None` if it is NULL or `idx` is past the end of
+    /// the vector.
+    ///
+    /// Invalid UTF-8, which can only come from vectors serialized outside
+    /// of this crate, is replaced with `U+FFFD`.
+    pub fn get(&self, idx: usize) -> Option<String> {
+        if idx >= self.len() {
+            return None;
+        }
+
+        let mut buf = vec![0u8; self.max_str_len() + 1];
+        let mut null = 0;
+
+        let res;
+        unsafe {
+            res = bitmagic_sys::BM_str_sparse_vector_get(
+                self.handle,
+                idx as u32,
+                buf.as_mut_ptr() as *mut c_char,
+                buf.len(),
+                &mut null,
+            );
+        }
+        _check_res(res);
+
+        if null == 0 {
+            Some(from_c_buf(&buf))
+        } else {
+            None
+        }
+    }
+
+    /// Iterate over the elements of the vector, with `None` for NULLs.
+    pub fn iter(&self) -> Iter<'_> {
+        let mut handle = ptr::null_mut();
+
+        let res;
+        unsafe {
+            res =
+                bitmagic_sys::BM_str_sparse_vector_iterator_construct(&mut handle, self.handle, 0);
+        }
+        _check_res(res);
+
+        Iter {
+            handle,
+            buf: vec![0u8; self.max_str_len() + 1],
+            remaining: self.len(),
+            _marker: PhantomData,
+        }
+    }
+
+    /// Remap the characters of the vector to a denser code, to save memory.
+    ///
+    /// This is best done once the vector is fully built: storing a string
+    /// with characters the remapping doesn't know about undoes it first.
+    pub fn remap(&mut self) {
+        let res;
+        unsafe {
+            res = bitmagic_sys::BM_str_sparse_vector_remap(self.handle);
+        }
+        _check_res(res);
+    }
+
+    /// Optimize the memory layout of the vector.
+    pub fn optimize(&mut self) {
+        let res;
+        unsafe {
+            res = bitmagic_sys::BM_str_sparse_vector_optimize(self.handle);
+        }
+        _check_res(res);
+    }
+
+    /// Memory statistics of the vector.
+    pub fn memory_stats(&self) -> MemoryStats {
+        let mut st = bitmagic_sys::BM_bvector_statistics {
+            bit_blocks: 0,
+            gap_blocks: 0,
+            max_serialize_mem: 0,
+            memory_used: 0,
+        };
+
+        let res;
+        unsafe {
+            res = bitmagic_sys::BM_str_sparse_vector_calc_stat(self.handle, &mut st);
+        }
+        _check_res(res);
+
+        MemoryStats {
+            bit_blocks: st.bit_blocks,
+            gap_blocks: st.gap_blocks,
+            max_serialize_mem: st.max_serialize_mem,
+            memory_used: st.memory_used,
+        }
+    }
+
+    /// The NULL bit-plane: its enabled bits are the elements that are not
+    /// NULL.
+    pub fn not_null(&self) -> BVectorRef<'_> {
+        let mut bv = ptr::null_mut();
+
+        let res;
+        unsafe {
+            res = bitmagic_sys::BM_str_sparse_vector_get_null_bvector(self.handle, &mut bv);
+        }
+        _check_res(res);
+
+        unsafe { BVectorRef::from_handle(bv) }
+    }
+
+    /// Serialize string sparse vector
+    pub fn serialize<W>(&self, wtr: W) -> Result<(), Box<dyn Error>>
+    where
+        W: Write,
+    {
+        let mut buf = ptr::null_mut();
+        let mut size = 0;
+
+        let res;
+        unsafe {
+            res = bitmagic_sys::BM_str_sparse_vector_serialize(self.handle, &mut buf, &mut size);
+        }
+        _check_res(res);
+
+        write_ext_buffer(buf, size, wtr)
+    }
+
+    /// Deserialize string sparse vector
+    pub fn deserialize<R>(mut rdr: R) -> Result<Self, Box<dyn Error>>
+    where
+        R: Read,
+    {
+        let mut buf = vec![];
+        rdr.read_to_end(&mut buf)?;
+
+        let snew = StrSparseVector::new();
+
+        let res;
+        unsafe {
+            res = bitmagic_sys::BM_str_sparse_vector_deserialize(
+                snew.handle,
+                buf.as_ptr(),
+                buf.len(),
+            );
+        }
+
+        if res != bitmagic_sys::BM_OK as i32 {
+            return Err("invalid serialized StrSparseVector".into());
+        }
+
+        Ok(snew)
+    }
+
+    /// Length of the longest string in the vector.
+    fn max_str_len(&self) -> usize {
+        let mut plen = 0;
+
+        let res;
+        unsafe {
+            res = bitmagic_sys::BM_str_sparse_vector_max_str_len(self.handle, &mut plen);
+        }
+        _check_res(res);
+
+        plen.min(StrSparseVector::MAX_STR_LEN)
+    }
+}
+
+pub(crate) fn to_c_str(value: &str) -> CString {
+    assert!(
+        value.len() <= StrSparseVector::MAX_STR_LEN,
+        "string longer than {} bytes",
+        StrSparseVector::MAX_STR_LEN
+    );
+    CString::new(value).expect("string contains a NUL byte")
+}
+
+pub(crate) fn from_c_buf(buf: &[u8]) -> String {
+    let len = buf.iter().position(|b| *b == 0).unwrap_or(buf.len());
+    String::from_utf8_lossy(&buf[..len]).into_owned()
+}
+
+impl Default for StrSparseVector {
+    fn default() -> Self {
+        StrSparseVector::new()
+    }
+}
+
+impl Clone for StrSparseVector {
+    fn clone(&self) -> Self {
+        let mut handle = ptr::null_mut();
+
+        let res;
+        unsafe {
+            res = bitmagic_sys::BM_str_sparse_vector_construct_copy(&mut handle, self.handle);
+        }
+        _check_res(res);
+
+        StrSparseVector { handle }
+    }
+}
+
+impl Drop for StrSparseVector {
+    fn drop(&mut self) {
+        unsafe {
+            bitmagic_sys::BM_str_sparse_vector_free(self.handle);
+        }
+    }
+}
+
+impl<'a> IntoIterator for &'a StrSparseVector {
+    type Item = Option<String>;
+    type IntoIter = Iter<'a>;
+
+    fn into_iter(self) -> Iter<'a> {
+        self.iter()
+    }
+}
+
+/// An iterator over the elements of a `StrSparseVector`.
+///
+/// This struct is created by the [`StrSparseVector::iter`] method.
+pub struct Iter<'a> {
+    handle: *mut c_void,
+    buf: Vec<u8>,
+    remaining: usize,
+    _marker: PhantomData<&'a StrSparseVector>,
+}
+
+impl<'a> Iterator for Iter<'a> {
+    type Item = Option<String>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let mut null = 0;
+        let mut valid = 0;
+
+        let res;
+        unsafe {
+            res = bitmagic_sys::BM_str_sparse_vector_iterator_next(
+                self.handle,
+                self.buf.as_mut_ptr() as *mut c_char,
+                self.buf.len(),
+                &mut null,
+                &mut valid,
+            );
+        }
+        _check_res(res);
+
+        if valid == 0 {
+            self.remaining = 0;
+            return None;
+        }
+        self.remaining -= 1;
+
+        if null == 0 {
+            Some(Some(from_c_buf(&self.buf)))
+        } else {
+            Some(None)
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl<'a> ExactSizeIterator for Iter<'a> {}
+
+impl<'a> Drop for Iter<'a> {
+    fn drop(&mut self) {
+        unsafe {
+            bitmagic_sys::BM_str_sparse_vector_iterator_free(self.handle);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::StrSparseVector;
+
+    fn sample() -> StrSparseVector {
+        let mut sv = StrSparseVector::new();
+        for name in &["ENSG00000139618", "ENSG00000141510", "", "sample-α"] {
+            sv.push(name);
+        }
+        sv.set(6, "ENSG00000012048");
+        sv
+    }
+
+    #[test]
+    fn push_set_get() {
+        let sv = sample();
+
+        assert_eq!(sv.len(), 7);
+        assert_eq!(sv.get(0), Some("ENSG00000139618".to_string()));
+        assert_eq!(sv.get(2), Some("".to_string()));
+        assert_eq!(sv.get(3), Some("sample-α".to_string()));
+        assert_eq!(sv.get(4), None);
+        assert_eq!(sv.get(6), Some("ENSG00000012048".to_string()));
+        assert_eq!(sv.get(7), None);
+
+        assert_eq!(
+            sv.not_null().ones().collect::<Vec<_>>(),
+            vec![0, 1, 2, 3, 6]
+        );
+    }
+
+    #[test]
+    fn iter() {
+        let sv = sample();
+
+        let all: Vec<Option<String>> = sv.iter().collect();
+        assert_eq!(all.len(), sv.len());
+        for (idx, value) in all.iter().enumerate() {
+            assert_eq!(*value, sv.get(idx));
+        }
+
+        let mut it = sv.iter();
+        it.next();
+        assert_eq!(it.len(), 6);
+        assert_eq!(StrSparseVector::new().iter().next(), None);
+    }
+
+    #[test]
+    fn remap() {
+        let mut sv = StrSparseVector::new();
+        for i in 0..10_000 {
+            sv.push(&format!("ENSG{:011}", i % 1000));
+        }
+        sv.optimize();
+        let before = sv.memory_stats();
+
+        sv.remap();
+        sv.optimize();
+        let after = sv.memory_stats();
+        assert!(after.memory_used <= before.memory_used);
+        assert_eq!(sv.get(1234), Some("ENSG00000000234".to_string()));
+
+        // only uses characters known to the remapping
+        sv.set(1, "ENSG00000000999");
+        assert_eq!(sv.get(1), Some("ENSG00000000999".to_string()));
+
+        sv.set(0, "xyz");
+        assert_eq!(sv.get(0), Some("xyz".to_string()));
+        assert_eq!(sv.get(1234), Some("ENSG00000000234".to_string()));
+    }
+
+    #[test]
+    fn serde() {
+        let mut sv = sample();
+        sv.remap();
+
+        let mut wtr = vec![];
+        sv.serialize(&mut wtr).unwrap();
+
+        let new_sv = StrSparseVector::deserialize(wtr.as_slice()).unwrap();
+        assert!(new_sv.iter().eq(sv.iter()));
+    }
+
+    #[should_panic]
+    #[test]
+    fn nul_byte() {
+        StrSparseVector::new().push("a\0b");
+    }
+
+    #[should_panic]
+    #[test]
+    fn too_long() {
+        let long = "x".repeat(StrSparseVector::MAX_STR_LEN + 1);
+        StrSparseVector::new().push(&long);
+    }
+}
@@ -0,0 +1,116 @@
+use crate::{BVector, CounterVector};
+
+impl BVector {
+    /// The bits enabled in at least `k` of `bvs`.
+    ///
+    /// The vectors are summed into bit-sliced counters, one vector at a
+    /// time, with whole-vector ripple-carry additions (see
+    /// [`CounterVector::add_set`]), and the counters are compared with `k`
+    /// plane by plane. This is not a block-level adder tree: each of the
+    /// `bvs.len()` additions makes a pass over every counter plane, so
+    /// the cost is O(n · log n) whole-vector operations for n vectors.
+    ///
+    /// **Panics** if `k` is 0, as every bit would match.
+    pub fn at_least_k(bvs: &[&BVector], k: usize) -> BVector {
+        assert!(k > 0, "threshold must be at least 1");
+        if k > bvs.len() {
+            return BVector::new();
+        }
+
+        counters(bvs).threshold(k as u64)
+    }
+
+    /// The bits enabled in more than half of `bvs`.
+    pub fn majority(bvs: &[&BVector]) -> BVector {
+        BVector::at_least_k(bvs, bvs.len() / 2 + 1)
+    }
+
+    /// The bits enabled in exactly `k` of `bvs`.
+    ///
+    /// **Panics** if `k` is 0, as every bit disabled everywhere would match.
+    pub fn exactly_k(bvs: &[&BVector], k: usize) -> BVector {
+        assert!(k > 0, "k must be at least 1");
+        if k > bvs.len() {
+            return BVector::new();
+        }
+
+        let counters = counters(bvs);
+        let mut exactly = counters.threshold(k as u64);
+        exactly.difference_with(&counters.threshold(k as u64 + 1));
+        exactly
+    }
+}
+
+fn counters(bvs: &[&BVector]) -> CounterVector {
+    let mut counters = CounterVector::new();
+    for bv in bvs {
+        counters.add_set(bv);
+    }
+    counters
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::BVector;
+
+    fn bvector(bits: &[usize]) -> BVector {
+        let mut bv = BVector::new();
+        for &bit in bits {
+            bv.insert(bit);
+        }
+        bv
+    }
+
+    fn ones(bv: &BVector) -> Vec<usize> {
+        bv.ones().collect()
+    }
+
+    #[test]
+    fn thresholds() {
+        let a = bvector(&[1, 2, 3, 4]);
+        let b = bvector(&[2, 3, 4, 100_000]);
+        let c = bvector(&[3, 4, 100_000]);
+        let d = bvector(&[4, 5]);
+        let bvs = [&a, &b, &c, &d];
+
+        assert_eq!(
+            ones(&BVector::at_least_k(&bvs, 1)),
+            vec![1, 2, 3, 4, 5, 100_000]
+        );
+        assert_eq!(ones(&BVector::at_least_k(&bvs, 2)), vec![2, 3, 4, 100_000]);
+        assert_eq!(ones(&BVector::at_least_k(&bvs, 4)), vec![4]);
+        assert_eq!(ones(&BVector::at_least_k(&bvs, 5)), Vec::<usize>::new());
+
+        assert_eq!(ones(&BVector::majority(&bvs)), vec![3, 4]);
+        assert_eq!(ones(&BVector::majority(&bvs[..3])), vec![2, 3, 4, 100_000]);
+
+        assert_eq!(ones(&BVector::exactly_k(&bvs, 1)), vec![1, 5]);
+        assert_eq!(ones(&BVector::exactly_k(&bvs, 2)), vec![2, 100_000]);
+        assert_eq!(ones(&BVector::exactly_k(&bvs, 4)), vec![4]);
+    }
+
+    #[test]
+    fn many_vectors() {
+        // bit i is enabled in the vectors 0..i
+        let bvs: Vec<BVector> = (0..50)
+            .map(|v| bvector(&(v + 1..60).collect::<Vec<_>>()))
+            .collect();
+        let refs: Vec<&BVector> = bvs.iter().collect();
+
+        assert_eq!(
+            ones(&BVector::at_least_k(&refs, 45)),
+            (45..60).collect::<Vec<_>>()
+        );
+        assert_eq!(ones(&BVector::exactly_k(&refs, 30)), vec![30]);
+        assert_eq!(
+            ones(&BVector::majority(&refs)),
+            (26..60).collect::<Vec<_>>()
+        );
+    }
+
+    #[should_panic]
+    #[test]
+    fn zero_threshold() {
+        BVector::at_least_k(&[&BVector::new()], 0);
+    }
+}
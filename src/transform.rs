@@ -0,0 +1,109 @@
+use crate::{_check_res, BVector, SparseVector};
+
+impl BVector {
+    /// Translate the ids in `self` through the 1:1 mapping `map`: id `i`
+    /// becomes `map[i]`.
+    ///
+    /// This joins two id spaces, e.g. the rows of two tables, in compressed
+    /// form. Ids that are NULL or past the end of `map` have no mapping and
+    /// are dropped.
+    ///
+    /// The result has a capacity of one past the largest value in `map`.
+    pub fn remap_through(&self, map: &SparseVector<u32>) -> BVector {
+        let remapped = BVector::new();
+
+        let res;
+        unsafe {
+            res = bitmagic_sys::BM_sparse_vector_u32_set2set_transform(
+                map.handle,
+                self.handle,
+                remapped.handle,
+            );
+        }
+        _check_res(res);
+
+        remapped
+    }
+
+    /// Inverse of [`remap_through`]: the ids `i` with `map[i]` in `self`.
+    ///
+    /// When several ids map to the same value, all of them are returned.
+    /// Every element of `map` is decoded once, so this is linear in the
+    /// length of `map`, and the result has that length as its capacity.
+    ///
+    /// [`remap_through`]: BVector::remap_through
+    pub fn unmap_through(&self, map: &SparseVector<u32>) -> BVector {
+        let unmapped = BVector::with_capacity(map.len());
+
+        let res;
+        unsafe {
+            res = bitmagic_sys::BM_sparse_vector_u32_set2set_inverse(
+                map.handle,
+                self.handle,
+                unmapped.handle,
+            );
+        }
+        _check_res(res);
+
+        unmapped
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{BVector, SparseVector};
+
+    fn ids(bv: &BVector) -> Vec<usize> {
+        bv.ones().collect()
+    }
+
+    fn sample() -> SparseVector<u32> {
+        let mut map = SparseVector::new();
+        map.import_from_slice(0, &[40, 10, 30, 20]);
+        map.set(10, 1000);
+        map
+    }
+
+    #[test]
+    fn remap_through() {
+        let map = sample();
+
+        let mut rows = BVector::new();
+        for &row in &[0, 2, 5, 10, 5000] {
+            rows.insert(row);
+        }
+
+        // 5 is NULL and 5000 is past the end
+        let remapped = rows.remap_through(&map);
+        assert_eq!(ids(&remapped), vec![30, 40, 1000]);
+        assert_eq!(remapped.len(), 1001);
+        assert!(remapped.contains(1000));
+        assert_eq!(
+            ids(&BVector::new().remap_through(&map)),
+            Vec::<usize>::new()
+        );
+    }
+
+    #[test]
+    fn unmap_through() {
+        let mut map = sample();
+        map.set(11, 10);
+
+        let mut targets = BVector::new();
+        for &target in &[10, 40, 50] {
+            targets.insert(target);
+        }
+
+        let unmapped = targets.unmap_through(&map);
+        assert_eq!(ids(&unmapped), vec![0, 1, 11]);
+        assert_eq!(unmapped.len(), map.len());
+
+        // NULL elements are not matched by a value of 0
+        targets.insert(0);
+        assert_eq!(ids(&targets.unmap_through(&map)), vec![0, 1, 11]);
+        assert_eq!(
+            ids(&targets.unmap_through(&map).remap_through(&map)),
+            vec![10, 40]
+        );
+    }
+}
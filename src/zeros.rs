@@ -0,0 +1,187 @@
+use crate::fixedbitset_api::IndexRange;
+use crate::{_check_res, BVector};
+
+impl BVector {
+    /// Position of the first disabled bit, or `None` if all the bits are
+    /// enabled.
+    ///
+    /// Only the bits within the capacity are searched or, for vectors
+    /// created with [`new`], all the addressable bits.
+    ///
+    /// [`new`]: BVector::new
+    pub fn first_zero(&self) -> Option<usize> {
+        self.next_zero(0)
+    }
+
+    /// Position of the first disabled bit at or after `from`, or `None` if
+    /// there is none. See [`first_zero`] for the bits searched.
+    ///
+    /// [`first_zero`]: BVector::first_zero
+    pub fn next_zero(&self, from: usize) -> Option<usize> {
+        let end = self.bit_limit();
+        if from >= end {
+            return None;
+        }
+
+        self.find_zero(from as u32).filter(|&bit| bit < end)
+    }
+
+    /// An iterator over the positions of the disabled bits, in increasing
+    /// order. See [`first_zero`] for the bits searched.
+    ///
+    /// Runs of enabled bits are skipped in a single call.
+    ///
+    /// [`first_zero`]: BVector::first_zero
+    pub fn zeros(&self) -> Zeros<'_> {
+        self.zeros_in(..)
+    }
+
+    /// An iterator over the positions of the disabled bits in `range`, in
+    /// increasing order.
+    ///
+    /// **Panics** if the range extends past the bits searched by
+    /// [`first_zero`].
+    ///
+    /// [`first_zero`]: BVector::first_zero
+    pub fn zeros_in<T: IndexRange>(&self, range: T) -> Zeros<'_> {
+        let limit = self.bit_limit();
+        let start = range.start().unwrap_or(0);
+        let end = range.end().unwrap_or(limit);
+        assert!(end <= limit, "Range extends past the end of the vector");
+
+        Zeros {
+            bv: self,
+            pos: start,
+            run_end: start,
+            end,
+        }
+    }
+
+    /// First disabled bit at or after `from`, ignoring the capacity.
+    fn find_zero(&self, from: u32) -> Option<usize> {
+        let mut pos = 0;
+        let mut found = 0;
+
+        let res;
+        unsafe {
+            res = bitmagic_sys::BM_bvector_find_zero(self.handle, from, &mut pos, &mut found);
+        }
+        _check_res(res);
+
+        if found == 1 {
+            Some(pos as usize)
+        } else {
+            None
+        }
+    }
+
+    /// First enabled bit after `bit`.
+    fn next_one(&self, bit: u32) -> Option<usize> {
+        let mut pnext = 0;
+
+        let res;
+        unsafe {
+            res = bitmagic_sys::BM_bvector_get_next(self.handle, bit, &mut pnext);
+        }
+        _check_res(res);
+
+        if pnext > bit {
+            Some(pnext as usize)
+        } else {
+            None
+        }
+    }
+}
+
+/// An iterator producing the indices of the disabled bits in a set.
+///
+/// This struct is created by the [`BVector::zeros`] and
+/// [`BVector::zeros_in`] methods.
+pub struct Zeros<'a> {
+    bv: &'a BVector,
+    pos: usize,
+    // end (excluded) of the run of disabled bits containing pos
+    run_end: usize,
+    end: usize,
+}
+
+impl<'a> Iterator for Zeros<'a> {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.pos >= self.end {
+            return None;
+        }
+
+        if self.pos >= self.run_end {
+            match self.bv.find_zero(self.pos as u32) {
+                Some(bit) if bit < self.end => {
+                    self.pos = bit;
+                    self.run_end = self
+                        .bv
+                        .next_one(bit as u32)
+                        .map_or(self.end, |one| one.min(self.end));
+                }
+                _ => {
+                    self.pos = self.end;
+                    return None;
+                }
+            }
+        }
+
+        let bit = self.pos;
+        self.pos += 1;
+        Some(bit)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::BVector;
+
+    #[test]
+    fn first_and_next_zero() {
+        let mut bv = BVector::with_capacity(10);
+        assert_eq!(bv.first_zero(), Some(0));
+
+        bv.set_range(0..4, true);
+        bv.insert(5);
+        assert_eq!(bv.first_zero(), Some(4));
+        assert_eq!(bv.next_zero(5), Some(6));
+        assert_eq!(bv.next_zero(9), Some(9));
+        assert_eq!(bv.next_zero(10), None);
+
+        bv.set_range(.., true);
+        assert_eq!(bv.first_zero(), None);
+    }
+
+    #[test]
+    fn unbounded() {
+        let mut bv = BVector::new();
+        for bit in 0..100_000 {
+            bv.insert(bit);
+        }
+
+        assert_eq!(bv.first_zero(), Some(100_000));
+        assert_eq!(bv.next_zero(200_000), Some(200_000));
+    }
+
+    #[test]
+    fn zeros() {
+        let mut bv = BVector::with_capacity(12);
+        bv.set_range(2..5, true);
+        bv.insert(7);
+        bv.insert(11);
+
+        assert_eq!(bv.zeros().collect::<Vec<_>>(), vec![0, 1, 5, 6, 8, 9, 10]);
+        assert_eq!(bv.zeros_in(3..9).collect::<Vec<_>>(), vec![5, 6, 8]);
+        assert_eq!(bv.zeros_in(2..5).count(), 0);
+        assert_eq!(bv.zeros_in(10..).collect::<Vec<_>>(), vec![10]);
+    }
+
+    #[should_panic]
+    #[test]
+    fn zeros_past_the_end() {
+        BVector::with_capacity(12).zeros_in(..13);
+    }
+}